@@ -0,0 +1,272 @@
+use crate::data_information::{DataField, DataInformation, SpecialFunction};
+use crate::data_send::DataSend;
+use crate::record::DataRecord;
+use mbus_frame::address::{Address, SecondaryAddress};
+use mbus_frame::ci::CiField;
+use mbus_frame::control::Control;
+use mbus_frame::frame::{Frame, LongFrameEncodeError};
+use thiserror::Error;
+
+/// VIF of the primary address of a slave (E111 1010)
+const PRIMARY_ADDRESS_VIF: u8 = 0x7A;
+
+/// Highest primary address that can be assigned to a slave
+const MAX_PRIMARY_ADDRESS: u8 = 250;
+
+/// Create a frame changing the primary address of a slave
+///
+/// The new address is sent as an SND-UD with CI 0x51, carrying a single
+/// 8-bit integer data record with VIF 0x7A, as defined in EN 13757-3
+/// (Annex E). The new address may be 0 to unconfigure the slave.
+pub fn set_primary_address(address: Address, new_address: u8) -> Result<Frame, CommandError> {
+    if new_address > MAX_PRIMARY_ADDRESS {
+        return Err(CommandError::InvalidPrimaryAddress(new_address));
+    }
+
+    let record = DataRecord::new(
+        DataInformation::new(DataField::Integer8),
+        PRIMARY_ADDRESS_VIF,
+        &[],
+        &[new_address],
+    );
+
+    Ok(data_send(address, vec![record])?)
+}
+
+/// Create a frame sending data records to a slave (SND-UD with CI 0x51)
+///
+/// Fails if the records don't fit in a single long frame.
+pub fn data_send(address: Address, records: Vec<DataRecord>) -> Result<Frame, LongFrameEncodeError> {
+    Ok(Frame::Long(DataSend::new(records).to_frame(address)?))
+}
+
+/// Create a frame resetting the application of a slave (CI 0x50)
+///
+/// Without a subcode, the reset is sent as a control frame. With a subcode,
+/// it's sent as a long frame carrying the subcode as its only data byte.
+pub fn application_reset(address: Address, subcode: Option<ResetSubcode>) -> Frame {
+    match subcode {
+        None => Frame::new_control(Control::Send, address, CiField::ApplicationReset),
+        Some(subcode) => Frame::new_long(
+            Control::Send,
+            address,
+            CiField::ApplicationReset,
+            &[subcode.into()],
+        ),
+    }
+}
+
+/// Create a frame selecting a slave by its secondary address (CI 0x52)
+///
+/// The frame is sent to the secondary addressing address (253). Any field of
+/// the secondary address may be a wildcard, in which case every slave
+/// matching the other fields is selected.
+pub fn select_slave(address: &SecondaryAddress) -> Frame {
+    Frame::new_long(
+        Control::Send,
+        Address::Secondary,
+        CiField::SelectSlave,
+        &address.to_bytes(),
+    )
+}
+
+/// Create a frame requesting a global readout from a slave
+///
+/// The request is sent as an SND-UD with CI 0x51, carrying the special DIF
+/// 0x7F. The slave then reports all its data records in the following
+/// RSP-UD telegrams.
+pub fn global_readout(address: Address) -> Frame {
+    Frame::new_long(
+        Control::Send,
+        address,
+        CiField::DataSend,
+        &[SpecialFunction::GlobalReadout.into()],
+    )
+}
+
+/// M-Bus Application Reset Subcode
+///
+/// The subcode of an application reset selects the application, or the
+/// telegram type, that the slave should reset to, as defined in EN 13757-3
+/// (§6.2). The lower nibble is reserved for the number of the telegram.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResetSubcode {
+    /// All telegrams (0x00)
+    All,
+
+    /// User data (0x10)
+    UserData,
+
+    /// Simple billing (0x20)
+    SimpleBilling,
+
+    /// Enhanced billing (0x30)
+    EnhancedBilling,
+
+    /// Multi-tariff billing (0x40)
+    MultiTariffBilling,
+
+    /// Instantaneous values (0x50)
+    InstantaneousValues,
+
+    /// Load management values for management (0x60)
+    LoadManagement,
+
+    /// Installation and startup (0x80)
+    Installation,
+
+    /// Testing (0x90)
+    Testing,
+
+    /// Calibration (0xA0)
+    Calibration,
+
+    /// Manufacturing (0xB0)
+    Manufacturing,
+
+    /// Development (0xC0)
+    Development,
+
+    /// Self-test (0xD0)
+    SelfTest,
+
+    /// Any other subcode, including the telegram number
+    Other(u8),
+}
+
+/// Implement conversion from u8 to ResetSubcode
+impl From<u8> for ResetSubcode {
+    fn from(value: u8) -> Self {
+        match value {
+            0x00 => ResetSubcode::All,
+            0x10 => ResetSubcode::UserData,
+            0x20 => ResetSubcode::SimpleBilling,
+            0x30 => ResetSubcode::EnhancedBilling,
+            0x40 => ResetSubcode::MultiTariffBilling,
+            0x50 => ResetSubcode::InstantaneousValues,
+            0x60 => ResetSubcode::LoadManagement,
+            0x80 => ResetSubcode::Installation,
+            0x90 => ResetSubcode::Testing,
+            0xA0 => ResetSubcode::Calibration,
+            0xB0 => ResetSubcode::Manufacturing,
+            0xC0 => ResetSubcode::Development,
+            0xD0 => ResetSubcode::SelfTest,
+            value => ResetSubcode::Other(value),
+        }
+    }
+}
+
+/// Implement conversion from ResetSubcode to u8
+impl From<ResetSubcode> for u8 {
+    fn from(subcode: ResetSubcode) -> Self {
+        match subcode {
+            ResetSubcode::All => 0x00,
+            ResetSubcode::UserData => 0x10,
+            ResetSubcode::SimpleBilling => 0x20,
+            ResetSubcode::EnhancedBilling => 0x30,
+            ResetSubcode::MultiTariffBilling => 0x40,
+            ResetSubcode::InstantaneousValues => 0x50,
+            ResetSubcode::LoadManagement => 0x60,
+            ResetSubcode::Installation => 0x80,
+            ResetSubcode::Testing => 0x90,
+            ResetSubcode::Calibration => 0xA0,
+            ResetSubcode::Manufacturing => 0xB0,
+            ResetSubcode::Development => 0xC0,
+            ResetSubcode::SelfTest => 0xD0,
+            ResetSubcode::Other(value) => value,
+        }
+    }
+}
+
+/// Errors that can occur when creating a command
+#[derive(Error, Debug)]
+pub enum CommandError {
+    #[error("invalid primary address {0}, expected 0 - 250")]
+    InvalidPrimaryAddress(u8),
+    #[error("failed to create frame: {0}")]
+    InvalidData(#[from] LongFrameEncodeError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_creates_a_set_primary_address_frame() {
+        let frame = set_primary_address(Address::Primary(0x01), 0x05).unwrap();
+        assert_eq!(
+            frame.to_bytes(),
+            [0x68, 0x06, 0x06, 0x68, 0x53, 0x01, 0x51, 0x01, 0x7A, 0x05, 0x25, 0x16]
+        );
+    }
+
+    #[test]
+    fn it_fails_to_set_a_reserved_primary_address() {
+        let err = set_primary_address(Address::Primary(0x01), 251).unwrap_err();
+        assert!(matches!(err, CommandError::InvalidPrimaryAddress(251)));
+    }
+
+    #[test]
+    fn it_creates_a_data_send_frame() {
+        let record = DataRecord::new(DataInformation::new(DataField::Integer8), 0x13, &[], &[0x2A]);
+        let frame = data_send(Address::Primary(0x01), vec![record]).unwrap();
+        assert_eq!(
+            frame.to_bytes(),
+            [0x68, 0x06, 0x06, 0x68, 0x53, 0x01, 0x51, 0x01, 0x13, 0x2A, 0xE3, 0x16]
+        );
+    }
+
+    #[test]
+    fn it_fails_to_send_records_that_dont_fit_in_a_frame() {
+        let record = DataRecord::new(DataInformation::new(DataField::Integer32), 0x13, &[], &[0x00; 4]);
+        let err = data_send(Address::Primary(0x01), vec![record; 64]).unwrap_err();
+        assert!(matches!(err, LongFrameEncodeError::DataTooLong(384)));
+    }
+
+    #[test]
+    fn it_creates_an_application_reset_frame() {
+        let frame = application_reset(Address::Primary(0x01), None);
+        assert_eq!(frame.to_bytes(), [0x68, 0x03, 0x03, 0x68, 0x53, 0x01, 0x50, 0xA4, 0x16]);
+
+        let frame = application_reset(Address::Primary(0x01), Some(ResetSubcode::UserData));
+        assert_eq!(
+            frame.to_bytes(),
+            [0x68, 0x04, 0x04, 0x68, 0x53, 0x01, 0x50, 0x10, 0xB4, 0x16]
+        );
+    }
+
+    #[test]
+    fn it_converts_reset_subcodes() {
+        for value in 0..=u8::MAX {
+            assert_eq!(u8::from(ResetSubcode::from(value)), value);
+        }
+    }
+
+    #[test]
+    fn it_creates_a_select_slave_frame() {
+        let address = SecondaryAddress::from_raw(0x12345678, 0x4024, 0x01, 0x07);
+        assert_eq!(
+            select_slave(&address).to_bytes(),
+            [
+                0x68, 0x0B, 0x0B, 0x68, 0x53, 0xFD, 0x52, 0x78, 0x56, 0x34, 0x12, 0x24, 0x40,
+                0x01, 0x07, 0x22, 0x16
+            ]
+        );
+    }
+
+    #[test]
+    fn it_creates_a_select_slave_frame_with_wildcards() {
+        let address = SecondaryAddress::wildcard().with_digit(7, 1);
+        let bytes = select_slave(&address).to_bytes();
+        assert_eq!(&bytes[7..15], [0xFF, 0xFF, 0xFF, 0x1F, 0xFF, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn it_creates_a_global_readout_frame() {
+        let frame = global_readout(Address::Primary(0x01));
+        assert_eq!(
+            frame.to_bytes(),
+            [0x68, 0x04, 0x04, 0x68, 0x53, 0x01, 0x51, 0x7F, 0x24, 0x16]
+        );
+    }
+}
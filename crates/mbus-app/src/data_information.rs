@@ -0,0 +1,485 @@
+use thiserror::Error;
+
+/// Extension bit of the DIF and DIFE bytes
+const EXTENSION_BIT: u8 = 0x80;
+
+/// Maximum number of DIFE bytes in a data information block
+///
+/// EN 13757-3 (§6.3.1) limits the extension chain to 10 bytes.
+const MAX_EXTENSIONS: usize = 10;
+
+/// Storage number bit of the DIF
+const DIF_STORAGE_BIT: u8 = 0x40;
+
+/// Subunit bit of a DIFE
+const DIFE_SUBUNIT_BIT: u8 = 0x40;
+
+/// M-Bus Data Information Block (DIB)
+///
+/// The data information block is made of a DIF and up to 10 DIFEs, as defined
+/// in EN 13757-3 (§6.3). It describes how the value of a data record is
+/// encoded, and which register of the slave it comes from:
+///
+/// - the data field coding gives the type and length of the value,
+/// - the function field tells whether the value is instantaneous, a minimum,
+///   a maximum or a value during an error state,
+/// - the storage number tells current values (0) apart from historic ones,
+/// - the tariff and subunit identify the register within the slave.
+///
+/// The storage number, tariff and subunit are assembled from the DIF and the
+/// DIFE chain, least significant bits first.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DataInformation {
+    /// Data field coding
+    data_field: DataField,
+
+    /// Function field
+    function: Function,
+
+    /// Storage number
+    storage_number: u64,
+
+    /// Tariff
+    tariff: u32,
+
+    /// Subunit
+    subunit: u16,
+
+    /// Minimum number of DIFE bytes
+    ///
+    /// Only set when a decoded DIFE chain is padded with DIFEs that don't
+    /// carry any bit set, so that it is encoded back as it was received.
+    extensions: usize,
+}
+
+impl DataInformation {
+    /// Create a new data information block, for an instantaneous current
+    /// value
+    pub fn new(data_field: DataField) -> Self {
+        Self {
+            data_field,
+            function: Function::Instantaneous,
+            storage_number: 0,
+            tariff: 0,
+            subunit: 0,
+            extensions: 0,
+        }
+    }
+
+    /// Set the function field
+    pub fn with_function(mut self, function: Function) -> Self {
+        self.function = function;
+        self
+    }
+
+    /// Set the storage number
+    pub fn with_storage_number(mut self, storage_number: u64) -> Self {
+        self.storage_number = storage_number;
+        self
+    }
+
+    /// Set the tariff
+    pub fn with_tariff(mut self, tariff: u32) -> Self {
+        self.tariff = tariff;
+        self
+    }
+
+    /// Set the subunit
+    pub fn with_subunit(mut self, subunit: u16) -> Self {
+        self.subunit = subunit;
+        self
+    }
+
+    /// Get the data field coding
+    pub fn data_field(&self) -> DataField {
+        self.data_field
+    }
+
+    /// Get the function field
+    pub fn function(&self) -> Function {
+        self.function
+    }
+
+    /// Get the storage number
+    pub fn storage_number(&self) -> u64 {
+        self.storage_number
+    }
+
+    /// Get the tariff
+    pub fn tariff(&self) -> u32 {
+        self.tariff
+    }
+
+    /// Get the subunit
+    pub fn subunit(&self) -> u16 {
+        self.subunit
+    }
+
+    /// Get the special function, if the DIF is a special function
+    pub fn special_function(&self) -> Option<SpecialFunction> {
+        match self.data_field {
+            DataField::Special(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    /// Decode a data information block from the start of a byte slice
+    ///
+    /// Returns the data information block, and the number of bytes it spans.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize), DataInformationDecodeError> {
+        let dif = *bytes.first().ok_or(DataInformationDecodeError::UnexpectedEnd)?;
+
+        // Special functions are single bytes, and reuse the function and
+        // storage bits to select the function
+        if dif & 0x0F == 0x0F {
+            return Ok((Self::new(DataField::Special(SpecialFunction::from(dif))), 1));
+        }
+
+        let mut information = Self::new(DataField::from(dif & 0x0F))
+            .with_function(Function::from((dif >> 4) & 0x03))
+            .with_storage_number(((dif & DIF_STORAGE_BIT) >> 6) as u64);
+
+        let mut extensions = 0;
+        let mut extended = dif & EXTENSION_BIT != 0;
+        while extended {
+            if extensions == MAX_EXTENSIONS {
+                return Err(DataInformationDecodeError::TooManyExtensions);
+            }
+
+            let dife = *bytes
+                .get(1 + extensions)
+                .ok_or(DataInformationDecodeError::UnexpectedEnd)?;
+
+            information.storage_number |= ((dife & 0x0F) as u64) << (1 + 4 * extensions);
+            information.tariff |= (((dife >> 4) & 0x03) as u32) << (2 * extensions);
+            information.subunit |= (((dife & DIFE_SUBUNIT_BIT) >> 6) as u16) << extensions;
+            extensions += 1;
+            extended = dife & EXTENSION_BIT != 0;
+        }
+
+        if extensions > information.required_extensions() {
+            information.extensions = extensions;
+        }
+
+        Ok((information, 1 + extensions))
+    }
+
+    /// Convert the data information block to bytes
+    ///
+    /// The DIFE chain is as long as needed to hold the storage number, tariff
+    /// and subunit, or as long as it was when decoded.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        if let DataField::Special(function) = self.data_field {
+            return vec![function.into()];
+        }
+
+        let extensions = self.extensions.max(self.required_extensions());
+
+        let mut dif = u8::from(self.data_field) | (u8::from(self.function) << 4);
+        dif |= ((self.storage_number & 0x01) as u8) << 6;
+        if extensions > 0 {
+            dif |= EXTENSION_BIT;
+        }
+
+        let mut bytes = vec![dif];
+        for index in 0..extensions {
+            let mut dife = ((self.storage_number >> (1 + 4 * index)) & 0x0F) as u8;
+            dife |= (((self.tariff >> (2 * index)) & 0x03) as u8) << 4;
+            dife |= (((self.subunit >> index) & 0x01) as u8) << 6;
+            if index + 1 < extensions {
+                dife |= EXTENSION_BIT;
+            }
+            bytes.push(dife);
+        }
+
+        bytes
+    }
+
+    /// Number of DIFE bytes needed to hold the storage number, tariff and
+    /// subunit
+    fn required_extensions(&self) -> usize {
+        let bits = |value: u64| (u64::BITS - value.leading_zeros()) as usize;
+
+        let storage = bits(self.storage_number >> 1).div_ceil(4);
+        let tariff = bits(self.tariff as u64).div_ceil(2);
+        let subunit = bits(self.subunit as u64);
+
+        storage.max(tariff).max(subunit)
+    }
+}
+
+/// Data field coding of the DIF
+///
+/// The data field coding gives the type and length of the value, as defined
+/// in EN 13757-3 (Table 4).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DataField {
+    NoData,
+    Integer8,
+    Integer16,
+    Integer24,
+    Integer32,
+    Real32,
+    Integer48,
+    Integer64,
+    SelectionForReadout,
+    Bcd2,
+    Bcd4,
+    Bcd6,
+    Bcd8,
+    VariableLength,
+    Bcd12,
+    Special(SpecialFunction),
+}
+
+impl DataField {
+    /// Get the length of the value in bytes
+    ///
+    /// Returns `None` for variable length data, whose length is given by the
+    /// LVAR byte, and for special functions.
+    pub fn length(&self) -> Option<usize> {
+        match self {
+            DataField::NoData | DataField::SelectionForReadout => Some(0),
+            DataField::Integer8 | DataField::Bcd2 => Some(1),
+            DataField::Integer16 | DataField::Bcd4 => Some(2),
+            DataField::Integer24 | DataField::Bcd6 => Some(3),
+            DataField::Integer32 | DataField::Real32 | DataField::Bcd8 => Some(4),
+            DataField::Integer48 | DataField::Bcd12 => Some(6),
+            DataField::Integer64 => Some(8),
+            DataField::VariableLength | DataField::Special(_) => None,
+        }
+    }
+}
+
+/// Implement conversion from the low nibble of the DIF to DataField
+///
+/// The special function nibble (0xF) doesn't select a single special
+/// function on its own, and is decoded as manufacturer specific data (0x0F).
+impl From<u8> for DataField {
+    fn from(value: u8) -> Self {
+        match value & 0x0F {
+            0x0 => DataField::NoData,
+            0x1 => DataField::Integer8,
+            0x2 => DataField::Integer16,
+            0x3 => DataField::Integer24,
+            0x4 => DataField::Integer32,
+            0x5 => DataField::Real32,
+            0x6 => DataField::Integer48,
+            0x7 => DataField::Integer64,
+            0x8 => DataField::SelectionForReadout,
+            0x9 => DataField::Bcd2,
+            0xA => DataField::Bcd4,
+            0xB => DataField::Bcd6,
+            0xC => DataField::Bcd8,
+            0xD => DataField::VariableLength,
+            0xE => DataField::Bcd12,
+            _ => DataField::Special(SpecialFunction::ManufacturerData),
+        }
+    }
+}
+
+/// Implement conversion from DataField to the low nibble of the DIF
+impl From<DataField> for u8 {
+    fn from(data_field: DataField) -> Self {
+        match data_field {
+            DataField::NoData => 0x0,
+            DataField::Integer8 => 0x1,
+            DataField::Integer16 => 0x2,
+            DataField::Integer24 => 0x3,
+            DataField::Integer32 => 0x4,
+            DataField::Real32 => 0x5,
+            DataField::Integer48 => 0x6,
+            DataField::Integer64 => 0x7,
+            DataField::SelectionForReadout => 0x8,
+            DataField::Bcd2 => 0x9,
+            DataField::Bcd4 => 0xA,
+            DataField::Bcd6 => 0xB,
+            DataField::Bcd8 => 0xC,
+            DataField::VariableLength => 0xD,
+            DataField::Bcd12 => 0xE,
+            DataField::Special(_) => 0xF,
+        }
+    }
+}
+
+/// Function field of the DIF
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Function {
+    Instantaneous,
+    Maximum,
+    Minimum,
+    Error,
+}
+
+impl From<u8> for Function {
+    fn from(value: u8) -> Self {
+        match value & 0x03 {
+            0x0 => Function::Instantaneous,
+            0x1 => Function::Maximum,
+            0x2 => Function::Minimum,
+            _ => Function::Error,
+        }
+    }
+}
+
+impl From<Function> for u8 {
+    fn from(function: Function) -> Self {
+        match function {
+            Function::Instantaneous => 0x0,
+            Function::Maximum => 0x1,
+            Function::Minimum => 0x2,
+            Function::Error => 0x3,
+        }
+    }
+}
+
+/// Special functions of the DIF
+///
+/// A DIF with the data field coding 0xF doesn't start a data record, but
+/// selects a special function, as defined in EN 13757-3 (Table 6).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SpecialFunction {
+    /// Manufacturer specific data follows, up to the end of the telegram
+    /// (0x0F)
+    ManufacturerData,
+
+    /// Manufacturer specific data follows, and more records follow in the
+    /// next telegram (0x1F)
+    MoreRecordsFollow,
+
+    /// Idle filler, to be skipped (0x2F)
+    IdleFiller,
+
+    /// Global readout request, for all storage numbers, tariffs, functions
+    /// and subunits (0x7F)
+    GlobalReadout,
+
+    /// Reserved special function, with the full DIF
+    Reserved(u8),
+}
+
+impl From<u8> for SpecialFunction {
+    fn from(value: u8) -> Self {
+        match value {
+            0x0F => SpecialFunction::ManufacturerData,
+            0x1F => SpecialFunction::MoreRecordsFollow,
+            0x2F => SpecialFunction::IdleFiller,
+            0x7F => SpecialFunction::GlobalReadout,
+            _ => SpecialFunction::Reserved(value),
+        }
+    }
+}
+
+impl From<SpecialFunction> for u8 {
+    fn from(function: SpecialFunction) -> Self {
+        match function {
+            SpecialFunction::ManufacturerData => 0x0F,
+            SpecialFunction::MoreRecordsFollow => 0x1F,
+            SpecialFunction::IdleFiller => 0x2F,
+            SpecialFunction::GlobalReadout => 0x7F,
+            SpecialFunction::Reserved(value) => value,
+        }
+    }
+}
+
+/// Errors that can occur when decoding a data information block
+#[derive(Error, Debug)]
+pub enum DataInformationDecodeError {
+    #[error("unexpected end of data information block")]
+    UnexpectedEnd,
+    #[error("too many DIFE bytes in data information block")]
+    TooManyExtensions,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_decodes_a_dif_without_extensions() {
+        let (information, length) = DataInformation::decode(&[0x04, 0x13]).unwrap();
+        assert_eq!(length, 1);
+        assert_eq!(information.data_field(), DataField::Integer32);
+        assert_eq!(information.function(), Function::Instantaneous);
+        assert_eq!(information.storage_number(), 0);
+        assert_eq!(information.tariff(), 0);
+        assert_eq!(information.subunit(), 0);
+    }
+
+    #[test]
+    fn it_assembles_the_storage_number_across_the_dife_chain() {
+        let (information, length) = DataInformation::decode(&[0xDA, 0x02]).unwrap();
+        assert_eq!(length, 2);
+        assert_eq!(information.data_field(), DataField::Bcd4);
+        assert_eq!(information.function(), Function::Maximum);
+        assert_eq!(information.storage_number(), 5);
+
+        let (information, _) = DataInformation::decode(&[0xC4, 0x8F, 0x03]).unwrap();
+        assert_eq!(information.storage_number(), 1 + (0x0F << 1) + (0x03 << 5));
+    }
+
+    #[test]
+    fn it_decodes_the_tariff_and_subunit() {
+        let (information, _) = DataInformation::decode(&[0x8B, 0xE0, 0x50]).unwrap();
+        assert_eq!(information.data_field(), DataField::Bcd6);
+        assert_eq!(information.tariff(), 0x02 | (0x01 << 2));
+        assert_eq!(information.subunit(), 0x03);
+    }
+
+    #[test]
+    fn it_decodes_special_functions() {
+        for (dif, function) in [
+            (0x0F, SpecialFunction::ManufacturerData),
+            (0x1F, SpecialFunction::MoreRecordsFollow),
+            (0x2F, SpecialFunction::IdleFiller),
+            (0x7F, SpecialFunction::GlobalReadout),
+            (0x3F, SpecialFunction::Reserved(0x3F)),
+        ] {
+            let (information, length) = DataInformation::decode(&[dif, 0xFF]).unwrap();
+            assert_eq!(length, 1);
+            assert_eq!(information.special_function(), Some(function));
+            assert_eq!(information.to_bytes(), vec![dif]);
+        }
+    }
+
+    #[test]
+    fn it_encodes_a_data_information_block() {
+        let information = DataInformation::new(DataField::Bcd4)
+            .with_function(Function::Maximum)
+            .with_storage_number(5);
+        assert_eq!(information.to_bytes(), vec![0xDA, 0x02]);
+
+        let information = DataInformation::new(DataField::Integer32)
+            .with_tariff(1)
+            .with_subunit(2);
+        assert_eq!(information.to_bytes(), vec![0x84, 0x90, 0x40]);
+    }
+
+    #[test]
+    fn it_round_trips_every_dif_with_extensions() {
+        for dif in 0x00..=0xFF_u8 {
+            for chain in [&[][..], &[0x00], &[0xFF, 0x7F], &[0x80, 0x80, 0x00]] {
+                let mut bytes = vec![dif];
+                if dif & EXTENSION_BIT != 0 && dif & 0x0F != 0x0F {
+                    bytes.extend_from_slice(if chain.is_empty() { &[0x00] } else { chain });
+                }
+
+                let (information, length) = DataInformation::decode(&bytes).unwrap();
+                assert_eq!(length, bytes.len());
+                assert_eq!(information.to_bytes(), bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_truncated_dife_chain() {
+        let err = DataInformation::decode(&[0x84, 0x80]).unwrap_err();
+        assert!(matches!(err, DataInformationDecodeError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn it_fails_to_decode_too_many_difes() {
+        let err = DataInformation::decode(&[0x84; 12]).unwrap_err();
+        assert!(matches!(err, DataInformationDecodeError::TooManyExtensions));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::data_information::{DataInformation, SpecialFunction};
+use crate::record::{DataRecord, DataRecordDecodeError};
+use mbus_frame::address::Address;
+use mbus_frame::ci::CiField;
+use mbus_frame::control::Control;
+use mbus_frame::frame::{LongFrame, LongFrameEncodeError};
+use thiserror::Error;
+
+/// M-Bus Data Send
+///
+/// The user data sent by a master to a slave in an SND-UD telegram with CI
+/// 0x51, as defined in EN 13757-3 (§6). Unlike the variable data response, it
+/// has no fixed data header, and is only made of data records, such as a new
+/// date and time or a new primary address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataSend {
+    /// Data records
+    records: Vec<DataRecord>,
+}
+
+impl DataSend {
+    /// Create a new data send
+    pub fn new(records: Vec<DataRecord>) -> Self {
+        Self { records }
+    }
+
+    /// Get the data records
+    pub fn records(&self) -> &[DataRecord] {
+        &self.records
+    }
+
+    /// Decode a data send from the user data following the CI field
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, DataSendDecodeError> {
+        let mut records = Vec::new();
+
+        let mut position = 0;
+        while position < bytes.len() {
+            let (information, _) = DataInformation::decode(&bytes[position..])
+                .map_err(DataRecordDecodeError::from)?;
+
+            if information.special_function() == Some(SpecialFunction::IdleFiller) {
+                position += 1;
+                continue;
+            }
+
+            let (record, length) = DataRecord::decode(&bytes[position..])?;
+            records.push(record);
+            position += length;
+        }
+
+        Ok(Self { records })
+    }
+
+    /// Convert the data send to bytes, following the CI field
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.records.iter().flat_map(DataRecord::to_bytes).collect()
+    }
+
+    /// Wrap the data send in an SND-UD long frame to the given address
+    ///
+    /// Fails if the records don't fit in a single long frame.
+    pub fn to_frame(&self, address: Address) -> Result<LongFrame, LongFrameEncodeError> {
+        LongFrame::try_new(Control::Send, address, CiField::DataSend, &self.to_bytes())
+    }
+}
+
+/// Implement conversion from a long frame to DataSend
+impl TryFrom<&LongFrame> for DataSend {
+    type Error = DataSendDecodeError;
+
+    fn try_from(frame: &LongFrame) -> Result<Self, Self::Error> {
+        match frame.ci() {
+            CiField::DataSend => Self::try_from_bytes(frame.data()),
+            ci => Err(DataSendDecodeError::UnsupportedCi(ci.into())),
+        }
+    }
+}
+
+/// Errors that can occur when decoding a data send
+#[derive(Error, Debug)]
+pub enum DataSendDecodeError {
+    #[error("unsupported CI field for data send: 0x{0:02X}")]
+    UnsupportedCi(u8),
+    #[error("failed to decode data record: {0}")]
+    DataRecord(#[from] DataRecordDecodeError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_information::DataField;
+    use crate::value::{Date, DateTime, Time, Value};
+
+    fn records() -> Vec<DataRecord> {
+        let date_time = DateTime {
+            date: Date { year: 2008, month: 5, day: 31 },
+            time: Time { hour: 23, minute: 50, second: 0 },
+            summer_time: false,
+            invalid: false,
+        };
+
+        vec![
+            DataRecord::with_value(
+                DataInformation::new(DataField::Integer32),
+                0x6D,
+                &[],
+                &Value::DateTime(date_time),
+            )
+            .unwrap(),
+            DataRecord::with_value(
+                DataInformation::new(DataField::Bcd8),
+                0x13,
+                &[],
+                &Value::Bcd(0),
+            )
+            .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn it_encodes_a_data_send() {
+        let data_send = DataSend::new(records());
+        assert_eq!(
+            data_send.to_bytes(),
+            [0x04, 0x6D, 0x32, 0x17, 0x1F, 0x15, 0x0C, 0x13, 0x00, 0x00, 0x00, 0x00]
+        );
+
+        let frame = data_send.to_frame(Address::Primary(0x05)).unwrap();
+        assert_eq!(frame.ci(), CiField::DataSend);
+        assert_eq!(frame.data(), data_send.to_bytes());
+    }
+
+    #[test]
+    fn it_round_trips_a_data_send_through_a_long_frame() {
+        let data_send = DataSend::new(records());
+        let frame = data_send.to_frame(Address::Primary(0x05)).unwrap();
+
+        let decoded = DataSend::try_from(&frame).unwrap();
+        assert_eq!(decoded, data_send);
+        assert_eq!(decoded.records()[1].value().unwrap(), Value::Bcd(0));
+    }
+
+    #[test]
+    fn it_skips_idle_fillers() {
+        let data_send = DataSend::try_from_bytes(&[0x2F, 0x01, 0x7A, 0x05, 0x2F]).unwrap();
+        assert_eq!(data_send.records().len(), 1);
+        assert_eq!(data_send.to_bytes(), [0x01, 0x7A, 0x05]);
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_frame_with_another_ci_field() {
+        let frame = LongFrame::new(Control::Send, Address::Primary(0x05), CiField::VariableDataResponse, &[0x00]);
+        let err = DataSend::try_from(&frame).unwrap_err();
+        assert!(matches!(err, DataSendDecodeError::UnsupportedCi(0x72)));
+    }
+}
@@ -0,0 +1,176 @@
+use mbus_frame::address::{SecondaryAddress, SECONDARY_ADDRESS_LENGTH};
+use mbus_meta::{Manufacturer, Medium};
+use thiserror::Error;
+
+/// Length of the fixed data header of a variable data response
+pub const FIXED_HEADER_LENGTH: usize = 12;
+
+const ACCESS_NUMBER_INDEX: usize = 8;
+const STATUS_INDEX: usize = 9;
+const SIGNATURE_INDEX: usize = 10;
+
+/// M-Bus Fixed Data Header
+///
+/// The fixed data header starts every variable data response (CI 0x72), as
+/// defined in EN 13757-3 (§6.3). It identifies the slave and reports its
+/// status:
+///
+/// | ID | Manufacturer | Version | Medium | Access No. | Status | Signature |
+/// |----|--------------|---------|--------|------------|--------|-----------|
+/// | 4  | 2            | 1       | 1      | 1          | 1      | 2         |
+///
+/// The first 8 bytes are the secondary address of the slave.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FixedHeader {
+    /// Secondary address of the slave
+    ///
+    /// Holds the identification number, manufacturer, version and medium.
+    address: SecondaryAddress,
+
+    /// Access number
+    ///
+    /// The access number is incremented by the slave after each response.
+    access_number: u8,
+
+    /// Status
+    ///
+    /// The status byte reports application errors, power and temporary
+    /// errors, as well as manufacturer specific flags.
+    status: u8,
+
+    /// Signature
+    ///
+    /// The signature is reserved for encryption, and is 0x0000 otherwise.
+    signature: u16,
+}
+
+impl FixedHeader {
+    /// Create a new fixed data header
+    pub fn new(address: SecondaryAddress, access_number: u8, status: u8, signature: u16) -> Self {
+        Self {
+            address,
+            access_number,
+            status,
+            signature,
+        }
+    }
+
+    /// Get the secondary address of the slave
+    pub fn address(&self) -> SecondaryAddress {
+        self.address
+    }
+
+    /// Get the identification number as a decimal number
+    ///
+    /// Returns `None` if the identification number isn't valid BCD.
+    pub fn id(&self) -> Option<u32> {
+        self.address.id()
+    }
+
+    /// Get the manufacturer of the slave
+    pub fn manufacturer(&self) -> Manufacturer {
+        self.address.manufacturer()
+    }
+
+    /// Get the version of the slave
+    pub fn version(&self) -> u8 {
+        self.address.version()
+    }
+
+    /// Get the medium of the slave
+    pub fn medium(&self) -> Medium {
+        self.address.medium()
+    }
+
+    /// Get the access number
+    pub fn access_number(&self) -> u8 {
+        self.access_number
+    }
+
+    /// Get the status byte
+    pub fn status(&self) -> u8 {
+        self.status
+    }
+
+    /// Get the signature
+    pub fn signature(&self) -> u16 {
+        self.signature
+    }
+
+    /// Convert the fixed data header to its 12-byte encoding
+    pub fn to_bytes(&self) -> [u8; FIXED_HEADER_LENGTH] {
+        let mut bytes = [0; FIXED_HEADER_LENGTH];
+        bytes[..SECONDARY_ADDRESS_LENGTH].copy_from_slice(&self.address.to_bytes());
+        bytes[ACCESS_NUMBER_INDEX] = self.access_number;
+        bytes[STATUS_INDEX] = self.status;
+        bytes[SIGNATURE_INDEX..].copy_from_slice(&self.signature.to_le_bytes());
+        bytes
+    }
+
+    /// Decode a fixed data header from its 12-byte encoding
+    pub fn from_bytes(bytes: &[u8; FIXED_HEADER_LENGTH]) -> Self {
+        let mut address = [0; SECONDARY_ADDRESS_LENGTH];
+        address.copy_from_slice(&bytes[..SECONDARY_ADDRESS_LENGTH]);
+
+        Self {
+            address: SecondaryAddress::from_bytes(&address),
+            access_number: bytes[ACCESS_NUMBER_INDEX],
+            status: bytes[STATUS_INDEX],
+            signature: u16::from_le_bytes([bytes[SIGNATURE_INDEX], bytes[SIGNATURE_INDEX + 1]]),
+        }
+    }
+}
+
+/// Implement conversion from a byte slice to FixedHeader
+impl TryFrom<&[u8]> for FixedHeader {
+    type Error = FixedHeaderDecodeError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let bytes: &[u8; FIXED_HEADER_LENGTH] = bytes
+            .try_into()
+            .map_err(|_| FixedHeaderDecodeError::InvalidLength(bytes.len()))?;
+
+        Ok(Self::from_bytes(bytes))
+    }
+}
+
+/// Errors that can occur when decoding a fixed data header
+#[derive(Error, Debug)]
+pub enum FixedHeaderDecodeError {
+    #[error("invalid length for fixed data header, expected 12, got {0}")]
+    InvalidLength(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BYTES: [u8; 12] = [
+        0x78, 0x56, 0x34, 0x12, 0x2D, 0x2C, 0x01, 0x07, 0x55, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn it_decodes_a_fixed_header() {
+        let header = FixedHeader::from_bytes(&BYTES);
+        assert_eq!(header.id(), Some(12345678));
+        assert_eq!(header.manufacturer(), Manufacturer::KAM);
+        assert_eq!(header.version(), 0x01);
+        assert_eq!(header.medium(), Medium::Water);
+        assert_eq!(header.access_number(), 0x55);
+        assert_eq!(header.status(), 0x00);
+        assert_eq!(header.signature(), 0x0000);
+    }
+
+    #[test]
+    fn it_encodes_a_fixed_header() {
+        let address = SecondaryAddress::new(12345678, Manufacturer::KAM, 0x01, Medium::Water).unwrap();
+        let header = FixedHeader::new(address, 0x55, 0x00, 0x0000);
+        assert_eq!(header.to_bytes(), BYTES);
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_fixed_header_with_invalid_length() {
+        let err = FixedHeader::try_from(&BYTES[..11]).unwrap_err();
+        assert!(matches!(err, FixedHeaderDecodeError::InvalidLength(11)));
+    }
+}
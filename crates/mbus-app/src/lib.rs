@@ -0,0 +1,18 @@
+pub mod command;
+pub mod data_information;
+pub mod data_send;
+pub mod header;
+pub mod readout;
+pub mod record;
+pub mod response;
+pub mod scan;
+pub mod value;
+pub mod value_information;
+
+pub use data_information::DataInformation;
+pub use data_send::DataSend;
+pub use header::FixedHeader;
+pub use record::DataRecord;
+pub use response::VariableDataResponse;
+pub use value::Value;
+pub use value_information::{Modifier, Quantity, Unit, ValueInformation};
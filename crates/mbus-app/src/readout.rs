@@ -0,0 +1,199 @@
+use crate::response::{VariableDataResponse, VariableDataResponseDecodeError};
+use mbus_frame::address::Address;
+use mbus_frame::link::{LinkError, LinkMaster};
+use mbus_frame::transport::Transport;
+use thiserror::Error;
+
+/// Default maximum number of telegrams read from a slave
+const DEFAULT_MAX_TELEGRAMS: usize = 16;
+
+/// M-Bus Multi-Telegram Readout
+///
+/// Slaves with more data records than fit in a single RSP-UD telegram end
+/// each telegram but the last with the special DIF 0x1F (more records
+/// follow). The readout keeps requesting user data (REQ-UD2) from the slave,
+/// with a toggled FCB, until a telegram no longer signals more records, and
+/// merges the records of every telegram into a single response.
+///
+/// To guard against slaves looping over their telegrams, the readout fails
+/// when the access number of a telegram was already seen, or when the slave
+/// sends more than the maximum number of telegrams.
+///
+/// ```no_run
+/// use mbus_app::readout::Readout;
+/// use mbus_frame::address::Address;
+/// use mbus_frame::link::LinkMaster;
+/// use mbus_frame::transport::tcp::TcpTransport;
+///
+/// let mut master = LinkMaster::new(TcpTransport::new("192.168.1.10:10001").unwrap());
+/// master.initialize(Address::Primary(1)).unwrap();
+///
+/// let response = Readout::new().run(&mut master, Address::Primary(1)).unwrap();
+/// println!("{} records", response.records().len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Readout {
+    /// Maximum number of telegrams read from the slave
+    max_telegrams: usize,
+}
+
+impl Readout {
+    /// Create a new readout of at most 16 telegrams
+    pub fn new() -> Self {
+        Self {
+            max_telegrams: DEFAULT_MAX_TELEGRAMS,
+        }
+    }
+
+    /// Change the maximum number of telegrams read from the slave
+    pub fn with_max_telegrams(mut self, max_telegrams: usize) -> Self {
+        self.max_telegrams = max_telegrams;
+        self
+    }
+
+    /// Get the maximum number of telegrams read from the slave
+    pub fn max_telegrams(&self) -> usize {
+        self.max_telegrams
+    }
+
+    /// Run the readout of a slave
+    ///
+    /// The readout starts with the next telegram of the slave. Initialize the
+    /// slave (SND-NKE) beforehand to start from its first telegram.
+    pub fn run<T: Transport>(
+        &self,
+        master: &mut LinkMaster<T>,
+        address: Address,
+    ) -> Result<VariableDataResponse, ReadoutError<T::Error>> {
+        let mut response = self.read(master, address)?;
+        let mut access_numbers = vec![response.header().access_number()];
+
+        while response.more_records_follow() {
+            if access_numbers.len() >= self.max_telegrams {
+                return Err(ReadoutError::TooManyTelegrams(self.max_telegrams));
+            }
+
+            let next = self.read(master, address)?;
+            let access_number = next.header().access_number();
+            if access_numbers.contains(&access_number) {
+                return Err(ReadoutError::RepeatedAccessNumber(access_number));
+            }
+
+            access_numbers.push(access_number);
+            response.append(next);
+        }
+
+        Ok(response)
+    }
+
+    /// Read the next telegram of the slave
+    fn read<T: Transport>(
+        &self,
+        master: &mut LinkMaster<T>,
+        address: Address,
+    ) -> Result<VariableDataResponse, ReadoutError<T::Error>> {
+        let frame = master.request_user_data(address)?;
+        Ok(VariableDataResponse::try_from(&frame)?)
+    }
+}
+
+impl Default for Readout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Errors that can occur during a multi-telegram readout
+#[derive(Error, Debug)]
+pub enum ReadoutError<E> {
+    #[error("failed to read telegram: {0}")]
+    Link(#[from] LinkError<E>),
+    #[error("failed to decode telegram: {0}")]
+    Response(#[from] VariableDataResponseDecodeError),
+    #[error("access number {0} was already read, the slave is repeating its telegrams")]
+    RepeatedAccessNumber(u8),
+    #[error("more records follow after the maximum of {0} telegrams")]
+    TooManyTelegrams(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::FixedHeader;
+    use mbus_frame::address::SecondaryAddress;
+    use mbus_frame::ci::CiField;
+    use mbus_frame::transport::simulator::{VirtualBus, VirtualSlave};
+
+    /// Secondary address of the slave under test
+    fn secondary() -> SecondaryAddress {
+        SecondaryAddress::from_raw(0x12345678, 0x4024, 0x01, 0x04)
+    }
+
+    /// Create a telegram with a single 8-bit record, optionally followed by
+    /// the more records follow DIF
+    fn telegram(access_number: u8, value: u8, more_records_follow: bool) -> Vec<u8> {
+        let mut data = FixedHeader::new(secondary(), access_number, 0x00, 0x0000).to_bytes().to_vec();
+        data.extend_from_slice(&[0x01, 0x13, value]);
+        if more_records_follow {
+            data.push(0x1F);
+        }
+        data
+    }
+
+    fn master(telegrams: &[Vec<u8>]) -> LinkMaster<VirtualBus> {
+        let slave = telegrams.iter().fold(VirtualSlave::new(1, secondary()), |slave, data| {
+            slave.with_response(CiField::VariableDataResponse, data)
+        });
+        let mut master = LinkMaster::new(VirtualBus::new().with_slave(slave));
+        master.initialize(Address::Primary(1)).unwrap();
+        master
+    }
+
+    #[test]
+    fn it_reads_a_single_telegram() {
+        let mut master = master(&[telegram(1, 0x10, false)]);
+
+        let response = Readout::new().run(&mut master, Address::Primary(1)).unwrap();
+        assert_eq!(response.records().len(), 1);
+        assert!(!response.more_records_follow());
+    }
+
+    #[test]
+    fn it_merges_the_records_of_every_telegram() {
+        let mut master = master(&[
+            telegram(1, 0x10, true),
+            telegram(2, 0x20, true),
+            telegram(3, 0x30, false),
+        ]);
+
+        let response = Readout::new().run(&mut master, Address::Primary(1)).unwrap();
+        let data: Vec<&[u8]> = response.records().iter().map(|record| record.data()).collect();
+        assert_eq!(data, [[0x10], [0x20], [0x30]]);
+        assert_eq!(response.header().access_number(), 1);
+        assert!(!response.more_records_follow());
+
+        // SND-NKE, then one REQ-UD2 per telegram
+        assert_eq!(master.transport().sent().len(), 4);
+    }
+
+    #[test]
+    fn it_fails_when_the_slave_repeats_its_telegrams() {
+        let mut master = master(&[telegram(1, 0x10, true), telegram(2, 0x20, true)]);
+
+        let err = Readout::new().run(&mut master, Address::Primary(1)).unwrap_err();
+        assert!(matches!(err, ReadoutError::RepeatedAccessNumber(1)));
+    }
+
+    #[test]
+    fn it_fails_after_the_maximum_number_of_telegrams() {
+        let telegrams: Vec<_> = (1..=5).map(|access_number| telegram(access_number, 0x10, true)).collect();
+        let mut master = master(&telegrams);
+
+        let err = Readout::new()
+            .with_max_telegrams(3)
+            .run(&mut master, Address::Primary(1))
+            .unwrap_err();
+        assert!(matches!(err, ReadoutError::TooManyTelegrams(3)));
+        assert_eq!(master.transport().sent().len(), 4);
+    }
+}
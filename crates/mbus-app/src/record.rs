@@ -0,0 +1,339 @@
+use crate::data_information::{DataField, DataInformation, DataInformationDecodeError};
+use crate::value::{lvar_length, Value, ValueDecodeError, ValueEncodeError};
+use crate::value_information::ValueInformation;
+use thiserror::Error;
+
+/// Extension bit of the VIF and VIFE bytes
+const EXTENSION_BIT: u8 = 0x80;
+
+/// Maximum number of VIFE bytes in a data record
+///
+/// EN 13757-3 (§6.3.1) limits the extension chain to 10 bytes.
+const MAX_EXTENSIONS: usize = 10;
+
+/// VIF announcing a unit given as plain text, without extension bit
+const PLAIN_TEXT_VIF: u8 = 0x7C;
+
+/// M-Bus Data Record
+///
+/// A data record is a single value reported by a slave in a variable data
+/// response, as defined in EN 13757-3 (§6.3). It is made of a data record
+/// header, which describes how the value is encoded and what it means,
+/// followed by the value itself:
+///
+/// | DIF | DIFE     | VIF | VIFE     | Data     |
+/// |-----|----------|-----|----------|----------|
+/// | 1   | 0 - 10   | 1   | 0 - 10   | 0 - N    |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataRecord {
+    /// Data information block (DIF and DIFE)
+    ///
+    /// The data information block holds the data field coding, the function
+    /// field, the storage number, the tariff and the subunit.
+    information: DataInformation,
+
+    /// Value information field (VIF)
+    ///
+    /// The VIF holds the unit and multiplier of the value.
+    vif: u8,
+
+    /// Value information field extensions (VIFE)
+    vife: Vec<u8>,
+
+    /// Unit given as plain text, as transmitted (last character first)
+    ///
+    /// Only present when the VIF is 0x7C or 0xFC.
+    plain_text_vif: Option<Vec<u8>>,
+
+    /// Data field, as transmitted
+    ///
+    /// For variable length data, the data field starts with its LVAR byte.
+    data: Vec<u8>,
+}
+
+impl DataRecord {
+    /// Create a new data record
+    pub fn new(information: DataInformation, vif: u8, vife: &[u8], data: &[u8]) -> Self {
+        Self {
+            information,
+            vif,
+            vife: vife.to_vec(),
+            plain_text_vif: None,
+            data: data.to_vec(),
+        }
+    }
+
+    /// Create a new data record, encoding its value with the data field
+    /// coding of the data information block
+    pub fn with_value(
+        information: DataInformation,
+        vif: u8,
+        vife: &[u8],
+        value: &Value,
+    ) -> Result<Self, ValueEncodeError> {
+        let data = value.encode(information.data_field())?;
+        Ok(Self::new(information, vif, vife, &data))
+    }
+
+    /// Get the data information block
+    pub fn data_information(&self) -> &DataInformation {
+        &self.information
+    }
+
+    /// Get the value information field (VIF)
+    pub fn vif(&self) -> u8 {
+        self.vif
+    }
+
+    /// Get the value information, decoded from the VIF and VIFE chain
+    pub fn value_information(&self) -> ValueInformation {
+        ValueInformation::decode(self.vif, &self.vife)
+    }
+
+    /// Get the value information field extensions (VIFE)
+    pub fn vife(&self) -> &[u8] {
+        &self.vife
+    }
+
+    /// Get the unit given as plain text, as transmitted (last character first)
+    pub fn plain_text_vif(&self) -> Option<&[u8]> {
+        self.plain_text_vif.as_deref()
+    }
+
+    /// Get the data field, as transmitted
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the value, decoded from the data field
+    pub fn value(&self) -> Result<Value, ValueDecodeError> {
+        Value::decode(self.information.data_field(), &self.value_information(), &self.data)
+    }
+
+    /// Get the value as a physical quantity, scaled by the exponent of the
+    /// value information
+    ///
+    /// Returns `None` for values that aren't numbers.
+    pub fn scaled_value(&self) -> Option<f64> {
+        let value = self.value().ok()?.as_f64()?;
+        Some(self.value_information().scale(value))
+    }
+
+    /// Decode a data record from the start of a byte slice
+    ///
+    /// Returns the data record, and the number of bytes it spans. The special
+    /// DIF values (0x0F, 0x1F, 0x2F and 0x7F) don't start a data record, and
+    /// must be handled by the caller.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize), DataRecordDecodeError> {
+        let (information, length) = DataInformation::decode(bytes)?;
+        if information.special_function().is_some() {
+            return Err(DataRecordDecodeError::UnsupportedDif(bytes[0]));
+        }
+
+        let mut reader = Reader::new(bytes);
+        reader.bytes(length)?;
+
+        let vif = reader.byte()?;
+        let vife = reader.extensions(vif)?;
+
+        let plain_text_vif = if vif & !EXTENSION_BIT == PLAIN_TEXT_VIF {
+            let length = reader.byte()? as usize;
+            Some(reader.bytes(length)?.to_vec())
+        } else {
+            None
+        };
+
+        let length = data_length(information.data_field(), reader.remaining())?;
+        let data = reader.bytes(length)?.to_vec();
+
+        let record = Self {
+            information,
+            vif,
+            vife,
+            plain_text_vif,
+            data,
+        };
+
+        Ok((record, reader.position))
+    }
+
+    /// Convert the data record to bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.information.to_bytes();
+        bytes.push(self.vif);
+        bytes.extend_from_slice(&self.vife);
+        if let Some(text) = &self.plain_text_vif {
+            bytes.push(text.len() as u8);
+            bytes.extend_from_slice(text);
+        }
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+}
+
+/// Get the length of the data field of a record, from its data field coding
+///
+/// For variable length data, the length is read from the LVAR byte at the
+/// start of the data field, and includes it.
+fn data_length(data_field: DataField, data: &[u8]) -> Result<usize, DataRecordDecodeError> {
+    if let Some(length) = data_field.length() {
+        return Ok(length);
+    }
+
+    let length = match data_field {
+        DataField::VariableLength => {
+            let lvar = *data.first().ok_or(DataRecordDecodeError::UnexpectedEnd)?;
+            1 + lvar_length(lvar).ok_or(DataRecordDecodeError::ReservedLvar(lvar))?
+        }
+        _ => return Err(DataRecordDecodeError::UnsupportedDif(data_field.into())),
+    };
+
+    Ok(length)
+}
+
+/// Cursor over the bytes of a data record
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// Get the bytes that haven't been read yet
+    fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.position..]
+    }
+
+    /// Read a single byte
+    fn byte(&mut self) -> Result<u8, DataRecordDecodeError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    /// Read the given number of bytes
+    fn bytes(&mut self, length: usize) -> Result<&'a [u8], DataRecordDecodeError> {
+        let bytes = self
+            .remaining()
+            .get(..length)
+            .ok_or(DataRecordDecodeError::UnexpectedEnd)?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    /// Read the chain of extension bytes following a byte with the extension
+    /// bit set
+    fn extensions(&mut self, first: u8) -> Result<Vec<u8>, DataRecordDecodeError> {
+        let mut extensions = Vec::new();
+        let mut extended = first & EXTENSION_BIT != 0;
+
+        while extended {
+            if extensions.len() == MAX_EXTENSIONS {
+                return Err(DataRecordDecodeError::TooManyExtensions);
+            }
+
+            let byte = self.byte()?;
+            extended = byte & EXTENSION_BIT != 0;
+            extensions.push(byte);
+        }
+
+        Ok(extensions)
+    }
+}
+
+/// Errors that can occur when decoding a data record
+#[derive(Error, Debug)]
+pub enum DataRecordDecodeError {
+    #[error("unexpected end of data record")]
+    UnexpectedEnd,
+    #[error("too many VIFE bytes in data record header")]
+    TooManyExtensions,
+    #[error("failed to decode data information block: {0}")]
+    DataInformation(#[from] DataInformationDecodeError),
+    #[error("unsupported DIF 0x{0:02X}")]
+    UnsupportedDif(u8),
+    #[error("reserved LVAR 0x{0:02X}")]
+    ReservedLvar(u8),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_information::{Modifier, Unit};
+
+    #[test]
+    fn it_decodes_a_data_record() {
+        let (record, length) = DataRecord::decode(&[0x03, 0x13, 0x15, 0x31, 0x00, 0xFF]).unwrap();
+        assert_eq!(length, 5);
+        assert_eq!(record.data_information(), &DataInformation::new(DataField::Integer24));
+        assert_eq!(record.vif(), 0x13);
+        assert_eq!(record.value_information().unit(), Unit::CubicMeter);
+        assert_eq!(record.value_information().exponent(), -3);
+        assert!(record.vife().is_empty());
+        assert_eq!(record.data(), &[0x15, 0x31, 0x00]);
+        assert_eq!(record.value().unwrap(), Value::Integer(0x3115));
+        assert_eq!(record.scaled_value(), Some(12.565));
+    }
+
+    #[test]
+    fn it_decodes_a_data_record_with_extensions() {
+        let bytes = [0x8B, 0x60, 0x84, 0x3E, 0x37, 0x18, 0x02];
+        let (record, length) = DataRecord::decode(&bytes).unwrap();
+        assert_eq!(length, 7);
+        assert_eq!(record.data_information().tariff(), 2);
+        assert_eq!(record.data_information().subunit(), 1);
+        assert_eq!(record.vif(), 0x84);
+        assert_eq!(record.vife(), &[0x3E]);
+        assert_eq!(record.value_information().modifiers(), &[Modifier::ValueAtBaseConditions]);
+        assert_eq!(record.data(), &[0x37, 0x18, 0x02]);
+        assert_eq!(record.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn it_decodes_a_variable_length_data_record() {
+        let bytes = [0x0D, 0x78, 0x03, 0x43, 0x42, 0x41];
+        let (record, length) = DataRecord::decode(&bytes).unwrap();
+        assert_eq!(length, 6);
+        assert_eq!(record.data(), &[0x03, 0x43, 0x42, 0x41]);
+    }
+
+    #[test]
+    fn it_decodes_a_data_record_with_a_plain_text_unit() {
+        let bytes = [0x01, 0x7C, 0x02, 0x62, 0x61, 0x2A];
+        let (record, length) = DataRecord::decode(&bytes).unwrap();
+        assert_eq!(length, 6);
+        assert_eq!(record.plain_text_vif(), Some(&[0x62, 0x61][..]));
+        assert_eq!(record.data(), &[0x2A]);
+        assert_eq!(record.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn it_creates_a_data_record_with_a_value() {
+        let information = DataInformation::new(DataField::Bcd8).with_storage_number(1);
+        let record = DataRecord::with_value(information, 0x13, &[], &Value::Bcd(12345678)).unwrap();
+        assert_eq!(record.to_bytes(), [0x4C, 0x13, 0x78, 0x56, 0x34, 0x12]);
+
+        let (decoded, _) = DataRecord::decode(&record.to_bytes()).unwrap();
+        assert_eq!(decoded, record);
+        assert_eq!(decoded.value().unwrap(), Value::Bcd(12345678));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_truncated_data_record() {
+        let err = DataRecord::decode(&[0x04, 0x13, 0x15, 0x31]).unwrap_err();
+        assert!(matches!(err, DataRecordDecodeError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_data_record_with_too_many_extensions() {
+        let err = DataRecord::decode(&[0x01, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0x01]).unwrap_err();
+        assert!(matches!(err, DataRecordDecodeError::TooManyExtensions));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_special_function_as_a_data_record() {
+        let err = DataRecord::decode(&[0x0F, 0x01]).unwrap_err();
+        assert!(matches!(err, DataRecordDecodeError::UnsupportedDif(0x0F)));
+    }
+}
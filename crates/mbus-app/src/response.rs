@@ -0,0 +1,229 @@
+use crate::data_information::{DataInformation, SpecialFunction};
+use crate::header::{FixedHeader, FixedHeaderDecodeError, FIXED_HEADER_LENGTH};
+use crate::record::{DataRecord, DataRecordDecodeError};
+use mbus_frame::ci::CiField;
+use mbus_frame::frame::LongFrame;
+use thiserror::Error;
+
+/// M-Bus Variable Data Response
+///
+/// The variable data response is the user data reported by a slave in an
+/// RSP-UD telegram with CI 0x72, as defined in EN 13757-3 (§6). It is made of
+/// a fixed data header, followed by any number of data records, and optional
+/// manufacturer specific data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableDataResponse {
+    /// Fixed data header
+    header: FixedHeader,
+
+    /// Data records
+    records: Vec<DataRecord>,
+
+    /// Manufacturer specific data, following a 0x0F or 0x1F DIF
+    manufacturer_data: Vec<u8>,
+
+    /// Whether the slave has more records to send in the next telegram
+    more_records_follow: bool,
+}
+
+impl VariableDataResponse {
+    /// Create a new variable data response
+    pub fn new(header: FixedHeader, records: Vec<DataRecord>) -> Self {
+        Self {
+            header,
+            records,
+            manufacturer_data: Vec::new(),
+            more_records_follow: false,
+        }
+    }
+
+    /// Get the fixed data header
+    pub fn header(&self) -> &FixedHeader {
+        &self.header
+    }
+
+    /// Get the data records
+    pub fn records(&self) -> &[DataRecord] {
+        &self.records
+    }
+
+    /// Get the manufacturer specific data
+    pub fn manufacturer_data(&self) -> &[u8] {
+        &self.manufacturer_data
+    }
+
+    /// Check whether the slave has more records to send in the next telegram
+    pub fn more_records_follow(&self) -> bool {
+        self.more_records_follow
+    }
+
+    /// Append the records of the next telegram of a multi-telegram response
+    ///
+    /// The fixed data header of the first telegram is kept, while the
+    /// manufacturer specific data and the more records follow flag are taken
+    /// from the next telegram.
+    pub fn append(&mut self, next: VariableDataResponse) {
+        self.records.extend(next.records);
+        self.manufacturer_data = next.manufacturer_data;
+        self.more_records_follow = next.more_records_follow;
+    }
+
+    /// Decode a variable data response from the user data following the CI
+    /// field
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, VariableDataResponseDecodeError> {
+        if bytes.len() < FIXED_HEADER_LENGTH {
+            return Err(VariableDataResponseDecodeError::InvalidSize(bytes.len()));
+        }
+
+        let header = FixedHeader::try_from(&bytes[..FIXED_HEADER_LENGTH])?;
+        let mut response = Self::new(header, Vec::new());
+
+        let mut position = FIXED_HEADER_LENGTH;
+        while position < bytes.len() {
+            let (information, _) = DataInformation::decode(&bytes[position..])
+                .map_err(DataRecordDecodeError::from)?;
+
+            match information.special_function() {
+                Some(SpecialFunction::IdleFiller) => position += 1,
+                Some(function @ (SpecialFunction::ManufacturerData | SpecialFunction::MoreRecordsFollow)) => {
+                    response.more_records_follow = function == SpecialFunction::MoreRecordsFollow;
+                    response.manufacturer_data = bytes[position + 1..].to_vec();
+                    break;
+                }
+                _ => {
+                    let (record, length) = DataRecord::decode(&bytes[position..])?;
+                    response.records.push(record);
+                    position += length;
+                }
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Convert the variable data response to bytes, following the CI field
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header.to_bytes().to_vec();
+        for record in &self.records {
+            bytes.extend_from_slice(&record.to_bytes());
+        }
+
+        if self.more_records_follow {
+            bytes.push(SpecialFunction::MoreRecordsFollow.into());
+            bytes.extend_from_slice(&self.manufacturer_data);
+        } else if !self.manufacturer_data.is_empty() {
+            bytes.push(SpecialFunction::ManufacturerData.into());
+            bytes.extend_from_slice(&self.manufacturer_data);
+        }
+
+        bytes
+    }
+}
+
+/// Implement conversion from a long frame to VariableDataResponse
+impl TryFrom<&LongFrame> for VariableDataResponse {
+    type Error = VariableDataResponseDecodeError;
+
+    fn try_from(frame: &LongFrame) -> Result<Self, Self::Error> {
+        match frame.ci() {
+            CiField::VariableDataResponse => Self::try_from_bytes(frame.data()),
+            ci => Err(VariableDataResponseDecodeError::UnsupportedCi(ci.into())),
+        }
+    }
+}
+
+/// Errors that can occur when decoding a variable data response
+#[derive(Error, Debug)]
+pub enum VariableDataResponseDecodeError {
+    #[error("unsupported CI field for variable data response: 0x{0:02X}")]
+    UnsupportedCi(u8),
+    #[error("invalid size for variable data response, expected at least 12, got {0}")]
+    InvalidSize(usize),
+    #[error("failed to decode fixed header: {0}")]
+    FixedHeader(#[from] FixedHeaderDecodeError),
+    #[error("failed to decode data record: {0}")]
+    DataRecord(#[from] DataRecordDecodeError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_information::{DataField, Function};
+    use mbus_frame::address::Address;
+    use mbus_frame::control::Control;
+    use mbus_meta::{Manufacturer, Medium};
+
+    /// Example of a variable data response, from EN 13757-3 (Annex E)
+    const DATA: [u8; 28] = [
+        0x78, 0x56, 0x34, 0x12, 0x24, 0x40, 0x01, 0x07, 0x55, 0x00, 0x00, 0x00, 0x03, 0x13, 0x15,
+        0x31, 0x00, 0xDA, 0x02, 0x3B, 0x13, 0x01, 0x8B, 0x60, 0x04, 0x37, 0x18, 0x02,
+    ];
+
+    fn frame(ci: CiField, data: &[u8]) -> LongFrame {
+        LongFrame::new(Control::Response, Address::Primary(0x02), ci, data)
+    }
+
+    #[test]
+    fn it_decodes_a_variable_data_response() {
+        let response =
+            VariableDataResponse::try_from(&frame(CiField::VariableDataResponse, &DATA)).unwrap();
+
+        let header = response.header();
+        assert_eq!(header.id(), Some(12345678));
+        assert_eq!(header.manufacturer(), Manufacturer::from(0x4024));
+        assert_eq!(header.version(), 0x01);
+        assert_eq!(header.medium(), Medium::Water);
+        assert_eq!(header.access_number(), 0x55);
+
+        let records = response.records();
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            records[0],
+            DataRecord::new(DataInformation::new(DataField::Integer24), 0x13, &[], &[0x15, 0x31, 0x00])
+        );
+
+        let information = DataInformation::new(DataField::Bcd4)
+            .with_function(Function::Maximum)
+            .with_storage_number(5);
+        assert_eq!(records[1], DataRecord::new(information, 0x3B, &[], &[0x13, 0x01]));
+
+        let information = DataInformation::new(DataField::Bcd6)
+            .with_tariff(2)
+            .with_subunit(1);
+        assert_eq!(records[2], DataRecord::new(information, 0x04, &[], &[0x37, 0x18, 0x02]));
+        assert!(!response.more_records_follow());
+        assert_eq!(response.to_bytes(), DATA);
+    }
+
+    #[test]
+    fn it_decodes_manufacturer_data_after_a_more_records_follow_dif() {
+        let mut data = DATA.to_vec();
+        data.extend_from_slice(&[0x2F, 0x1F, 0x01, 0x02]);
+        let response = VariableDataResponse::try_from_bytes(&data).unwrap();
+        assert_eq!(response.records().len(), 3);
+        assert!(response.more_records_follow());
+        assert_eq!(response.manufacturer_data(), &[0x01, 0x02]);
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_frame_with_another_ci_field() {
+        let err = VariableDataResponse::try_from(&frame(CiField::FixedDataResponse, &DATA))
+            .unwrap_err();
+        assert!(matches!(err, VariableDataResponseDecodeError::UnsupportedCi(0x73)));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_truncated_fixed_header() {
+        let err = VariableDataResponse::try_from_bytes(&DATA[..8]).unwrap_err();
+        assert!(matches!(err, VariableDataResponseDecodeError::InvalidSize(8)));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_truncated_data_record() {
+        let err = VariableDataResponse::try_from_bytes(&DATA[..27]).unwrap_err();
+        assert!(matches!(
+            err,
+            VariableDataResponseDecodeError::DataRecord(DataRecordDecodeError::UnexpectedEnd)
+        ));
+    }
+}
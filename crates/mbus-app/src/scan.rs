@@ -0,0 +1,841 @@
+use crate::header::{FixedHeader, FIXED_HEADER_LENGTH};
+use mbus_frame::address::{Address, SecondaryAddress};
+use mbus_frame::ci::CiField;
+use mbus_frame::frame::LongFrame;
+use mbus_frame::link::{LinkError, LinkMaster};
+use mbus_frame::transport::Transport;
+use mbus_meta::{Manufacturer, Medium};
+use std::ops::RangeInclusive;
+
+/// Highest primary address of a slave
+const MAX_PRIMARY_ADDRESS: u8 = 250;
+
+/// Default number of times a frame is repeated during a scan
+const DEFAULT_RETRIES: usize = 1;
+
+/// Wildcard value of a digit of the identification number
+const DIGIT_WILDCARD: u8 = 0xF;
+
+/// Number of digits of the identification number
+const ID_DIGITS: usize = 8;
+
+/// M-Bus Primary Address Scan
+///
+/// The scan walks a range of primary addresses, and for each of them
+/// initializes the slave (SND-NKE) and requests its user data (REQ-UD2).
+/// Slaves that reply are reported with their response, while addresses where
+/// the reply is garbled, typically because several slaves share the same
+/// primary address, are reported as collisions.
+///
+/// ```no_run
+/// use mbus_app::scan::PrimaryScan;
+/// use mbus_frame::link::LinkMaster;
+/// use mbus_frame::transport::tcp::TcpTransport;
+///
+/// let mut master = LinkMaster::new(TcpTransport::new("192.168.1.10:10001").unwrap());
+/// let report = PrimaryScan::new().with_range(1..=20).run(&mut master).unwrap();
+///
+/// for device in report.devices() {
+///     println!("{}: {:?}", device.address(), device.header());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrimaryScan {
+    /// Primary addresses to scan
+    range: RangeInclusive<u8>,
+
+    /// Number of times a frame is repeated when the slave doesn't reply
+    retries: usize,
+}
+
+impl PrimaryScan {
+    /// Create a new scan of every primary address (0 - 250)
+    pub fn new() -> Self {
+        Self {
+            range: 0..=MAX_PRIMARY_ADDRESS,
+            retries: DEFAULT_RETRIES,
+        }
+    }
+
+    /// Set the primary addresses to scan
+    ///
+    /// Addresses above 250 aren't primary addresses, and are skipped.
+    pub fn with_range(mut self, range: RangeInclusive<u8>) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Set the number of times a frame is repeated when the slave doesn't
+    /// reply
+    pub fn with_retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Run the scan
+    ///
+    /// The retries of the scan replace the retries of the link master for
+    /// the duration of the scan. Only errors of the transport abort the scan.
+    pub fn run<T: Transport>(
+        &self,
+        master: &mut LinkMaster<T>,
+    ) -> Result<ScanReport, LinkError<T::Error>> {
+        let mut report = ScanReport::default();
+
+        with_retries(master, self.retries, |master| {
+            self.range
+                .clone()
+                .take_while(|&address| address <= MAX_PRIMARY_ADDRESS)
+                .try_for_each(|address| {
+                    match probe(master, address) {
+                        Ok(Some(response)) => {
+                            report.devices.push(ScannedDevice::new(address, response))
+                        }
+                        Ok(None) => {}
+                        Err(LinkError::InvalidFrame(..) | LinkError::UnexpectedFrame(_)) => {
+                            report.collisions.push(address)
+                        }
+                        Err(LinkError::Transport(err)) => return Err(LinkError::Transport(err)),
+                        Err(_) => {}
+                    }
+
+                    Ok(())
+                })
+        })?;
+
+        Ok(report)
+    }
+}
+
+impl Default for PrimaryScan {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run a function with the given retries on the link master, restoring its
+/// own retries afterwards
+fn with_retries<T: Transport, R>(
+    master: &mut LinkMaster<T>,
+    retries: usize,
+    f: impl FnOnce(&mut LinkMaster<T>) -> R,
+) -> R {
+    let previous = master.retries();
+    master.set_retries(retries);
+    let result = f(master);
+    master.set_retries(previous);
+    result
+}
+
+/// Probe a single primary address
+///
+/// Returns the response of the slave, or `None` if no slave replied.
+fn probe<T: Transport>(
+    master: &mut LinkMaster<T>,
+    address: u8,
+) -> Result<Option<LongFrame>, LinkError<T::Error>> {
+    let address = Address::from(address);
+
+    match master.initialize(address) {
+        Ok(()) => {}
+        Err(LinkError::Timeout(_) | LinkError::Nack) => return Ok(None),
+        Err(err) => return Err(err),
+    }
+
+    match master.request_user_data(address) {
+        Ok(response) => Ok(Some(response)),
+        Err(LinkError::Timeout(_) | LinkError::Nack) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Get the fixed data header of a response, if it's a variable data response
+/// with a long header (CI 0x72)
+fn fixed_header(response: &LongFrame) -> Option<FixedHeader> {
+    match response.ci() {
+        CiField::VariableDataResponse => response
+            .data()
+            .get(..FIXED_HEADER_LENGTH)
+            .and_then(|bytes| FixedHeader::try_from(bytes).ok()),
+        _ => None,
+    }
+}
+
+/// Result of a primary address scan
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    /// Slaves that replied
+    devices: Vec<ScannedDevice>,
+
+    /// Primary addresses where the reply was garbled
+    collisions: Vec<u8>,
+}
+
+impl ScanReport {
+    /// Get the slaves that replied, by increasing primary address
+    pub fn devices(&self) -> &[ScannedDevice] {
+        &self.devices
+    }
+
+    /// Get the primary addresses where the reply was garbled, typically
+    /// because several slaves share the same primary address
+    pub fn collisions(&self) -> &[u8] {
+        &self.collisions
+    }
+}
+
+/// Slave found by a primary address scan
+#[derive(Debug, Clone)]
+pub struct ScannedDevice {
+    /// Primary address of the slave
+    address: u8,
+
+    /// Fixed data header of the response, if the slave sent one
+    header: Option<FixedHeader>,
+
+    /// Response of the slave to the REQ-UD2
+    response: LongFrame,
+}
+
+impl ScannedDevice {
+    fn new(address: u8, response: LongFrame) -> Self {
+        let header = fixed_header(&response);
+        Self {
+            address,
+            header,
+            response,
+        }
+    }
+
+    /// Get the primary address of the slave
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Get the fixed data header of the response
+    ///
+    /// Returns `None` if the response isn't a variable data response with a
+    /// long header (CI 0x72).
+    pub fn header(&self) -> Option<&FixedHeader> {
+        self.header.as_ref()
+    }
+
+    /// Get the response of the slave to the REQ-UD2
+    pub fn response(&self) -> &LongFrame {
+        &self.response
+    }
+}
+
+/// M-Bus Secondary Address Search
+///
+/// The search finds the secondary address of every slave on the bus, with
+/// the wildcard tree algorithm:
+///
+/// - the master selects the slaves matching a secondary address, where the
+///   digits of the identification number not yet known are wildcards,
+/// - when no slave acknowledges the selection, the branch is dropped,
+/// - when a single slave replies, its secondary address is read from its
+///   response to a REQ-UD2,
+/// - when several slaves reply at once, their replies are garbled, and the
+///   search descends into the ten values of the next wildcard digit, from the
+///   most significant digit to the least significant one.
+///
+/// Overlapping replies may also merge into a valid frame, which then looks
+/// like the reply of a single slave. A reply to a pattern that still has
+/// wildcard digits is therefore confirmed by selecting the full secondary
+/// address read from its header, and the search descends into the next
+/// wildcard digit if the confirmation fails.
+///
+/// A merged reply may still be confirmed when it matches the address of one
+/// of the slaves that replied. [`SecondarySearch::with_exhaustive`] makes the
+/// search descend after confirmed replies as well, to find the slaves hidden
+/// behind them, at the cost of ten more selections per wildcard digit for
+/// every slave found.
+///
+/// The manufacturer, version and medium may be fixed to only find matching
+/// slaves, and are wildcards otherwise.
+#[derive(Debug, Clone)]
+pub struct SecondarySearch {
+    /// Secondary address the search starts from
+    pattern: SecondaryAddress,
+
+    /// Number of times a frame is repeated when the slave doesn't reply
+    retries: usize,
+
+    /// Whether to descend into the next wildcard digit after confirmed
+    /// replies as well
+    exhaustive: bool,
+}
+
+impl SecondarySearch {
+    /// Create a new search for every slave on the bus
+    pub fn new() -> Self {
+        Self {
+            pattern: SecondaryAddress::wildcard(),
+            retries: DEFAULT_RETRIES,
+            exhaustive: false,
+        }
+    }
+
+    /// Only find slaves of the given manufacturer
+    pub fn with_manufacturer(self, manufacturer: Manufacturer) -> Self {
+        self.with_manufacturer_id(manufacturer.into())
+    }
+
+    /// Only find slaves with the given raw manufacturer identifier, e.g. of a
+    /// manufacturer that isn't listed in [`Manufacturer`]
+    pub fn with_manufacturer_id(mut self, manufacturer: u16) -> Self {
+        self.pattern = self.pattern.with_manufacturer_id(manufacturer);
+        self
+    }
+
+    /// Only find slaves with the given version
+    pub fn with_version(mut self, version: u8) -> Self {
+        self.pattern = self.pattern.with_version(version);
+        self
+    }
+
+    /// Only find slaves of the given medium
+    pub fn with_medium(mut self, medium: Medium) -> Self {
+        self.pattern = self.pattern.with_medium(medium);
+        self
+    }
+
+    /// Set the number of times a frame is repeated when the slave doesn't
+    /// reply
+    pub fn with_retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set whether to descend into the next wildcard digit after confirmed
+    /// replies as well, to find slaves whose replies merged into the reply of
+    /// another slave
+    ///
+    /// This is off by default, as it makes the search much slower, most of
+    /// the additional selections ending in a timeout.
+    pub fn with_exhaustive(mut self, exhaustive: bool) -> Self {
+        self.exhaustive = exhaustive;
+        self
+    }
+
+    /// Run the search
+    ///
+    /// The retries of the search replace the retries of the link master for
+    /// the duration of the search. Only errors of the transport abort the
+    /// search.
+    pub fn run<T: Transport>(
+        &self,
+        master: &mut LinkMaster<T>,
+    ) -> Result<SearchReport, LinkError<T::Error>> {
+        let mut report = SearchReport::default();
+
+        with_retries(master, self.retries, |master| {
+            // Deselect any slave left selected by a previous exchange
+            if let Err(LinkError::Transport(err)) = master.initialize(Address::Secondary) {
+                return Err(LinkError::Transport(err));
+            }
+
+            search(master, self.pattern, self.exhaustive, &mut report)
+        })?;
+
+        Ok(report)
+    }
+}
+
+impl Default for SecondarySearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Outcome of selecting the slaves matching a secondary address
+enum Selection {
+    /// No slave replied
+    None,
+
+    /// A single slave replied to the selection and to the REQ-UD2
+    Single(LongFrame),
+
+    /// Several slaves replied at once
+    Collision,
+}
+
+/// Search the slaves matching a secondary address, descending into the next
+/// wildcard digit on collisions and unconfirmed replies
+fn search<T: Transport>(
+    master: &mut LinkMaster<T>,
+    pattern: SecondaryAddress,
+    exhaustive: bool,
+    report: &mut SearchReport,
+) -> Result<(), LinkError<T::Error>> {
+    let wildcard = (0..ID_DIGITS)
+        .rev()
+        .find(|&index| pattern.digit(index) == DIGIT_WILDCARD);
+
+    match (select(master, pattern)?, wildcard) {
+        (Selection::None, _) => {}
+        (Selection::Single(response), None) => {
+            let device = FoundDevice::new(pattern, response);
+            if !report.contains(&device.address) {
+                report.devices.push(device);
+            }
+        }
+        (Selection::Single(response), Some(index)) => {
+            let confirmed = confirm(master, pattern, &response)?;
+            let descend_further = exhaustive || confirmed.is_none();
+            if let Some(device) = confirmed
+                && !report.contains(&device.address)
+            {
+                report.devices.push(device);
+            }
+
+            if descend_further {
+                descend(master, pattern, index, exhaustive, report)?;
+            }
+        }
+        (Selection::Collision, Some(index)) => descend(master, pattern, index, exhaustive, report)?,
+        (Selection::Collision, None) => report.collisions.push(pattern),
+    }
+
+    Ok(())
+}
+
+/// Search the ten values of a wildcard digit of a secondary address
+///
+/// Fully specified addresses of slaves that were already found are skipped.
+fn descend<T: Transport>(
+    master: &mut LinkMaster<T>,
+    pattern: SecondaryAddress,
+    index: usize,
+    exhaustive: bool,
+    report: &mut SearchReport,
+) -> Result<(), LinkError<T::Error>> {
+    for digit in 0..=9 {
+        let pattern = pattern.with_digit(index, digit);
+        if index == 0 && report.contains(&pattern) {
+            continue;
+        }
+
+        search(master, pattern, exhaustive, report)?;
+    }
+
+    Ok(())
+}
+
+/// Confirm the reply to a pattern with wildcard digits, by selecting the
+/// secondary address read from its header on its own
+///
+/// Returns the slave if it replies again with the same secondary address.
+fn confirm<T: Transport>(
+    master: &mut LinkMaster<T>,
+    pattern: SecondaryAddress,
+    response: &LongFrame,
+) -> Result<Option<FoundDevice>, LinkError<T::Error>> {
+    let Some(address) = fixed_header(response).map(|header| header.address()) else {
+        return Ok(None);
+    };
+
+    if address.has_wildcard() || !pattern.matches(&address) {
+        return Ok(None);
+    }
+
+    match select(master, address)? {
+        Selection::Single(response) => {
+            let device = FoundDevice::new(address, response);
+            Ok((device.address == address).then_some(device))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Select the slaves matching a secondary address, and request the user data
+/// of the selected slave
+fn select<T: Transport>(
+    master: &mut LinkMaster<T>,
+    pattern: SecondaryAddress,
+) -> Result<Selection, LinkError<T::Error>> {
+    let selected = master.send_user_data(Address::Secondary, CiField::SelectSlave, &pattern.to_bytes());
+    let response = selected.and_then(|_| master.request_user_data(Address::Secondary));
+
+    match response {
+        Ok(response) => Ok(Selection::Single(response)),
+        Err(LinkError::Timeout(_) | LinkError::Nack) => Ok(Selection::None),
+        Err(LinkError::InvalidFrame(..) | LinkError::UnexpectedFrame(_)) => Ok(Selection::Collision),
+        Err(err) => Err(err),
+    }
+}
+
+/// Result of a secondary address search
+#[derive(Debug, Clone, Default)]
+pub struct SearchReport {
+    /// Slaves that were found
+    devices: Vec<FoundDevice>,
+
+    /// Fully specified secondary addresses where the replies were still
+    /// garbled
+    collisions: Vec<SecondaryAddress>,
+}
+
+impl SearchReport {
+    /// Check whether a slave matching a secondary address was already found
+    fn contains(&self, address: &SecondaryAddress) -> bool {
+        self.devices.iter().any(|device| address.matches(&device.address))
+    }
+
+    /// Get the slaves that were found
+    pub fn devices(&self) -> &[FoundDevice] {
+        &self.devices
+    }
+
+    /// Get the secondary addresses where the replies were still garbled once
+    /// every digit of the identification number was known, typically because
+    /// several slaves share the same identification number
+    pub fn collisions(&self) -> &[SecondaryAddress] {
+        &self.collisions
+    }
+}
+
+/// Slave found by a secondary address search
+#[derive(Debug, Clone)]
+pub struct FoundDevice {
+    /// Secondary address of the slave
+    address: SecondaryAddress,
+
+    /// Response of the slave to the REQ-UD2
+    response: LongFrame,
+}
+
+impl FoundDevice {
+    fn new(pattern: SecondaryAddress, response: LongFrame) -> Self {
+        let header = fixed_header(&response);
+        Self {
+            address: header.map_or(pattern, |header| header.address()),
+            response,
+        }
+    }
+
+    /// Get the secondary address of the slave
+    ///
+    /// The address is read from the fixed data header of the response. If the
+    /// response has no long header, the address used to select the slave is
+    /// returned, and may contain wildcards.
+    pub fn address(&self) -> SecondaryAddress {
+        self.address
+    }
+
+    /// Get the response of the slave to the REQ-UD2
+    pub fn response(&self) -> &LongFrame {
+        &self.response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mbus_frame::control::Control;
+    use mbus_frame::frame::Frame;
+    use mbus_frame::frame::SingleCharacterFrame;
+    use mbus_frame::transport::simulator::{VirtualBus, VirtualSlave};
+    use mbus_frame::transport::ReceiveError;
+    use std::collections::HashMap;
+
+    /// Behaviour of the slaves at a primary address
+    enum Slave {
+        /// A single slave, replying with the given header
+        Meter(FixedHeader),
+
+        /// Several slaves, garbling each other's replies
+        Collision,
+    }
+
+    /// Bus answering the frames sent by the master from its slaves
+    #[derive(Default)]
+    struct Bus {
+        slaves: HashMap<u8, Slave>,
+        last: Option<Frame>,
+        sent: usize,
+    }
+
+    impl Bus {
+        fn slave(mut self, address: u8, slave: Slave) -> Self {
+            self.slaves.insert(address, slave);
+            self
+        }
+    }
+
+    impl Transport for Bus {
+        type Error = &'static str;
+
+        fn send(&mut self, frame: &Frame) -> Result<(), Self::Error> {
+            self.sent += 1;
+            self.last = Some(frame.clone());
+            Ok(())
+        }
+
+        fn receive(&mut self) -> Result<Frame, ReceiveError<Self::Error>> {
+            let frame = self.last.take().ok_or(ReceiveError::Timeout)?;
+            let address = u8::from(frame.address().unwrap());
+
+            match self.slaves.get(&address) {
+                None => Err(ReceiveError::Timeout),
+                Some(Slave::Collision) => {
+                    Err(Frame::try_from_bytes(&[0x10, 0x08, 0x01, 0x00, 0x16]).unwrap_err().into())
+                }
+                Some(Slave::Meter(header)) => match frame.control().unwrap().control() {
+                    Control::Initialize => Ok(Frame::new_single(SingleCharacterFrame::Ack)),
+                    _ => Ok(Frame::new_long(
+                        Control::Response,
+                        Address::from(address),
+                        CiField::VariableDataResponse,
+                        &header.to_bytes(),
+                    )),
+                },
+            }
+        }
+    }
+
+    fn header(id: u32) -> FixedHeader {
+        let address = SecondaryAddress::from_raw(id, 0x4024, 0x01, 0x07);
+        FixedHeader::new(address, 0x00, 0x00, 0x0000)
+    }
+
+    #[test]
+    fn it_finds_the_slaves_on_the_bus() {
+        let bus = Bus::default()
+            .slave(1, Slave::Meter(header(0x12345678)))
+            .slave(42, Slave::Meter(header(0x87654321)));
+        let mut master = LinkMaster::new(bus);
+
+        let report = PrimaryScan::new().run(&mut master).unwrap();
+        let devices = report.devices();
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].address(), 1);
+        assert_eq!(devices[0].header().unwrap().id(), Some(12345678));
+        assert_eq!(devices[1].address(), 42);
+        assert_eq!(devices[1].header().unwrap().id(), Some(87654321));
+        assert!(report.collisions().is_empty());
+    }
+
+    #[test]
+    fn it_reports_collisions_separately() {
+        let bus = Bus::default()
+            .slave(1, Slave::Meter(header(0x12345678)))
+            .slave(2, Slave::Collision);
+        let mut master = LinkMaster::new(bus);
+
+        let report = PrimaryScan::new().with_range(0..=5).run(&mut master).unwrap();
+        assert_eq!(report.devices().len(), 1);
+        assert_eq!(report.collisions(), &[2]);
+    }
+
+    #[test]
+    fn it_scans_the_given_range_with_the_given_retries() {
+        let mut master = LinkMaster::new(Bus::default()).with_retries(5);
+
+        let report = PrimaryScan::new()
+            .with_range(10..=19)
+            .with_retries(2)
+            .run(&mut master)
+            .unwrap();
+        assert!(report.devices().is_empty());
+
+        // Each SND-NKE is sent once, and repeated twice
+        assert_eq!(master.transport().sent, 30);
+        assert_eq!(master.retries(), 5);
+    }
+
+    #[test]
+    fn it_skips_addresses_above_250() {
+        let mut master = LinkMaster::new(Bus::default()).with_retries(0);
+        PrimaryScan::new()
+            .with_range(250..=255)
+            .with_retries(0)
+            .run(&mut master)
+            .unwrap();
+        assert_eq!(master.transport().sent, 1);
+    }
+
+    /// Bus of slaves answering to secondary addressing
+    #[derive(Default)]
+    struct SecondaryBus {
+        slaves: Vec<FixedHeader>,
+        selected: Vec<usize>,
+        last: Option<Frame>,
+    }
+
+    impl SecondaryBus {
+        fn new(slaves: &[FixedHeader]) -> Self {
+            Self {
+                slaves: slaves.to_vec(),
+                ..Self::default()
+            }
+        }
+
+        fn garbled() -> ReceiveError<&'static str> {
+            Frame::try_from_bytes(&[0x10, 0x08, 0x01, 0x00, 0x16]).unwrap_err().into()
+        }
+    }
+
+    impl Transport for SecondaryBus {
+        type Error = &'static str;
+
+        fn send(&mut self, frame: &Frame) -> Result<(), Self::Error> {
+            self.last = Some(frame.clone());
+            Ok(())
+        }
+
+        fn receive(&mut self) -> Result<Frame, ReceiveError<Self::Error>> {
+            let frame = self.last.take().ok_or(ReceiveError::Timeout)?;
+            if frame.address() != Some(Address::Secondary) {
+                return Err(ReceiveError::Timeout);
+            }
+
+            if let Frame::Long(long) = &frame {
+                let pattern = SecondaryAddress::from_bytes(long.data().try_into().unwrap());
+                self.selected = (0..self.slaves.len())
+                    .filter(|&index| pattern.matches(&self.slaves[index].address()))
+                    .collect();
+
+                // Simultaneous acknowledgments overlap into a valid one
+                return match self.selected.len() {
+                    0 => Err(ReceiveError::Timeout),
+                    _ => Ok(Frame::new_single(SingleCharacterFrame::Ack)),
+                };
+            }
+
+            match frame.control().unwrap().control() {
+                Control::Initialize => {
+                    self.selected.clear();
+                    Err(ReceiveError::Timeout)
+                }
+                _ => match self.selected[..] {
+                    [] => Err(ReceiveError::Timeout),
+                    [index] => Ok(Frame::new_long(
+                        Control::Response,
+                        Address::Secondary,
+                        CiField::VariableDataResponse,
+                        &self.slaves[index].to_bytes(),
+                    )),
+                    _ => Err(Self::garbled()),
+                },
+            }
+        }
+    }
+
+    fn slave(id: u32, medium: u8) -> FixedHeader {
+        let address = SecondaryAddress::from_raw(id, 0x4024, 0x01, medium);
+        FixedHeader::new(address, 0x00, 0x00, 0x0000)
+    }
+
+    fn ids(report: &SearchReport) -> Vec<Option<u32>> {
+        report.devices().iter().map(|device| device.address().id()).collect()
+    }
+
+    #[test]
+    fn it_finds_every_slave_by_secondary_address() {
+        let bus = SecondaryBus::new(&[
+            slave(0x12345678, 0x07),
+            slave(0x87654321, 0x07),
+            slave(0x12345679, 0x07),
+            slave(0x12000000, 0x07),
+        ]);
+        let mut master = LinkMaster::new(bus);
+
+        let report = SecondarySearch::new().run(&mut master).unwrap();
+        assert_eq!(
+            ids(&report),
+            [Some(12000000), Some(12345678), Some(12345679), Some(87654321)]
+        );
+        assert!(report.collisions().is_empty());
+    }
+
+    #[test]
+    fn it_only_finds_slaves_matching_the_filters() {
+        let bus = SecondaryBus::new(&[
+            slave(0x12345678, 0x07),
+            slave(0x12345679, 0x03),
+            slave(0x22345679, 0x07),
+        ]);
+        let mut master = LinkMaster::new(bus);
+
+        let report = SecondarySearch::new()
+            .with_medium(Medium::Water)
+            .with_version(0x01)
+            .run(&mut master)
+            .unwrap();
+        assert_eq!(ids(&report), [Some(12345678), Some(22345679)]);
+    }
+
+    #[test]
+    fn it_only_finds_slaves_of_an_unlisted_manufacturer() {
+        // XYZ isn't a listed manufacturer
+        let unlisted = FixedHeader::new(SecondaryAddress::from_raw(0x22345679, 0x633A, 0x01, 0x07), 0x00, 0x00, 0x0000);
+        let bus = SecondaryBus::new(&[slave(0x12345678, 0x07), unlisted]);
+        let mut master = LinkMaster::new(bus);
+
+        let report = SecondarySearch::new().with_manufacturer_id(0x633A).run(&mut master).unwrap();
+        assert_eq!(ids(&report), [Some(22345679)]);
+        assert_eq!(report.devices()[0].address().manufacturer_id(), 0x633A);
+    }
+
+    #[test]
+    fn it_reports_slaves_sharing_a_secondary_address_as_collisions() {
+        let bus = SecondaryBus::new(&[slave(0x12345678, 0x07), slave(0x12345678, 0x07)]);
+        let mut master = LinkMaster::new(bus).with_retries(0);
+
+        let report = SecondarySearch::new().with_retries(0).run(&mut master).unwrap();
+        assert!(report.devices().is_empty());
+        assert_eq!(report.collisions().len(), 1);
+        assert_eq!(report.collisions()[0].id(), Some(12345678));
+    }
+
+    /// Bus of virtual slaves, whose overlapping replies merge bitwise
+    fn virtual_bus(ids: &[u32]) -> VirtualBus {
+        ids.iter().fold(VirtualBus::new(), |bus, &id| {
+            bus.with_slave(VirtualSlave::new(0, SecondaryAddress::from_raw(id, 0x4024, 0x01, 0x07)))
+        })
+    }
+
+    #[test]
+    fn it_finds_slaves_hidden_behind_merged_replies() {
+        // The replies of 12345678 and 12345679 merge into a valid reply of 12345678
+        let mut master = LinkMaster::new(virtual_bus(&[0x12345678, 0x12345679]));
+
+        let report = SecondarySearch::new().with_exhaustive(true).run(&mut master).unwrap();
+        assert_eq!(ids(&report), [Some(12345678), Some(12345679)]);
+        assert!(report.collisions().is_empty());
+    }
+
+    #[test]
+    fn it_finds_every_slave_on_a_virtual_bus() {
+        let mut master = LinkMaster::new(virtual_bus(&[0x12345678, 0x87654321, 0x12345679, 0x12000000]));
+
+        let report = SecondarySearch::new().with_exhaustive(true).run(&mut master).unwrap();
+        assert_eq!(
+            ids(&report),
+            [Some(12000000), Some(12345678), Some(12345679), Some(87654321)]
+        );
+        assert!(report.collisions().is_empty());
+    }
+
+    #[test]
+    fn it_stops_at_a_confirmed_reply() {
+        let mut master = LinkMaster::new(virtual_bus(&[0x12345678]));
+
+        let report = SecondarySearch::new().run(&mut master).unwrap();
+        assert_eq!(ids(&report), [Some(12345678)]);
+
+        // Selection of the wildcard, then of the address read from the header
+        let selections = master.transport().sent().iter().filter(|frame| matches!(frame, Frame::Long(_)));
+        assert_eq!(selections.count(), 2);
+    }
+
+    #[test]
+    fn it_misses_slaves_hidden_behind_a_confirmed_merged_reply_by_default() {
+        let mut master = LinkMaster::new(virtual_bus(&[0x12345678, 0x12345679]));
+
+        let report = SecondarySearch::new().run(&mut master).unwrap();
+        assert_eq!(ids(&report), [Some(12345678)]);
+    }
+}
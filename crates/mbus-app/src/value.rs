@@ -0,0 +1,1131 @@
+use crate::data_information::DataField;
+use crate::value_information::{Modifier, Quantity, ValueInformation};
+use std::fmt;
+use thiserror::Error;
+
+/// Sign digit of a negative BCD value
+const BCD_NEGATIVE: u8 = 0xF;
+
+/// LVAR of a date and time with time zone (type M), as 7 bytes of binary data
+const TYPE_M_LVAR: u8 = 0xE7;
+
+/// M-Bus Data Record Value
+///
+/// The value of a data record, decoded from its data field according to the
+/// data field coding of the DIF, as defined in EN 13757-3 (Annex A). Date
+/// and time values are recognised from the VIF and VIFE chain.
+///
+/// Date and time types F, G, I, J, K and M are decoded into their fields.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// No data, or selection for readout
+    None,
+
+    /// Signed integer (type B)
+    Integer(i64),
+
+    /// 32-bit real (type H)
+    Real(f32),
+
+    /// BCD value (type A), including negative values
+    Bcd(i64),
+
+    /// BCD value containing hex digits (A - F), as used by slaves to mark
+    /// errors, with the raw digits
+    BcdError(u128),
+
+    /// Date (type G)
+    Date(Date),
+
+    /// Time (type J)
+    Time(Time),
+
+    /// Date and time (type F, or type I with seconds)
+    DateTime(DateTime),
+
+    /// Date and time with time zone (type M)
+    ZonedDateTime(ZonedDateTime),
+
+    /// Daylight saving period (type K)
+    DaylightSaving(DaylightSaving),
+
+    /// Text, from variable length ASCII data
+    Text(String),
+
+    /// Binary data (type D bit fields, or variable length binary data)
+    Binary(Vec<u8>),
+}
+
+impl Value {
+    /// Decode a value from the data field of a record
+    ///
+    /// For variable length data, the data field starts with its LVAR byte.
+    pub fn decode(
+        data_field: DataField,
+        information: &ValueInformation,
+        data: &[u8],
+    ) -> Result<Self, ValueDecodeError> {
+        if let Some(length) = data_field.length()
+            && data.len() != length
+        {
+            return Err(ValueDecodeError::InvalidLength(length, data.len()));
+        }
+
+        let value = match data_field {
+            DataField::NoData | DataField::SelectionForReadout => Value::None,
+            DataField::Integer16 if is_date_time(information) => Value::Date(Date::from_type_g(data)),
+            DataField::Integer24 if is_date_time(information) => Value::Time(Time::from_type_j(data)),
+            DataField::Integer32 if is_date_time(information) => {
+                Value::DateTime(DateTime::from_type_f(data))
+            }
+            DataField::Integer48 if is_date_time(information) => {
+                Value::DateTime(DateTime::from_type_i(data))
+            }
+            DataField::Integer32 if information.quantity() == Quantity::DaylightSaving => {
+                Value::DaylightSaving(DaylightSaving::from_type_k(data))
+            }
+            DataField::VariableLength if is_date_time(information) && data.first() == Some(&TYPE_M_LVAR) => {
+                if data.len() != 8 {
+                    return Err(ValueDecodeError::InvalidLength(8, data.len()));
+                }
+
+                Value::ZonedDateTime(ZonedDateTime::from_type_m(&data[1..]))
+            }
+            DataField::Integer8
+            | DataField::Integer16
+            | DataField::Integer24
+            | DataField::Integer32
+            | DataField::Integer48
+            | DataField::Integer64
+                if is_bit_field(information) =>
+            {
+                Value::Binary(data.to_vec())
+            }
+            DataField::Integer8
+            | DataField::Integer16
+            | DataField::Integer24
+            | DataField::Integer32
+            | DataField::Integer48
+            | DataField::Integer64 => Value::Integer(integer(data)),
+            DataField::Real32 => Value::Real(f32::from_le_bytes([data[0], data[1], data[2], data[3]])),
+            DataField::Bcd2
+            | DataField::Bcd4
+            | DataField::Bcd6
+            | DataField::Bcd8
+            | DataField::Bcd12 => bcd(data),
+            DataField::VariableLength => variable_length(data)?,
+            DataField::Special(_) => return Err(ValueDecodeError::UnsupportedDataField),
+        };
+
+        Ok(value)
+    }
+
+    /// Encode the value into the data field of a record
+    ///
+    /// This is the inverse of [`Value::decode`]: for variable length data,
+    /// the data field starts with its LVAR byte. Fails if the value can't be
+    /// represented with the given data field coding.
+    pub fn encode(&self, data_field: DataField) -> Result<Vec<u8>, ValueEncodeError> {
+        let length = data_field.length().unwrap_or_default();
+
+        let bytes = match (data_field, self) {
+            (DataField::NoData | DataField::SelectionForReadout, Value::None) => Vec::new(),
+            (DataField::Integer16, Value::Date(date)) => date.to_type_g()?.to_vec(),
+            (DataField::Integer24, Value::Time(time)) => time.to_type_j()?.to_vec(),
+            (DataField::Integer32, Value::DateTime(date_time)) => date_time.to_type_f()?.to_vec(),
+            (DataField::Integer48, Value::DateTime(date_time)) => date_time.to_type_i()?.to_vec(),
+            (DataField::Integer32, Value::DaylightSaving(daylight_saving)) => {
+                daylight_saving.to_type_k()?.to_vec()
+            }
+            (DataField::VariableLength, Value::ZonedDateTime(zoned)) => {
+                let mut bytes = vec![TYPE_M_LVAR];
+                bytes.extend_from_slice(&zoned.to_type_m()?);
+                bytes
+            }
+            (
+                DataField::Integer8
+                | DataField::Integer16
+                | DataField::Integer24
+                | DataField::Integer32
+                | DataField::Integer48
+                | DataField::Integer64,
+                Value::Integer(value),
+            ) => encode_integer(*value, length)?,
+            (
+                DataField::Integer8
+                | DataField::Integer16
+                | DataField::Integer24
+                | DataField::Integer32
+                | DataField::Integer48
+                | DataField::Integer64,
+                Value::Binary(bytes),
+            ) if bytes.len() == length => bytes.clone(),
+            (DataField::Real32, Value::Real(value)) => value.to_le_bytes().to_vec(),
+            (
+                DataField::Bcd2 | DataField::Bcd4 | DataField::Bcd6 | DataField::Bcd8 | DataField::Bcd12,
+                Value::Bcd(value),
+            ) => encode_bcd(*value, length)?,
+            (
+                DataField::Bcd2 | DataField::Bcd4 | DataField::Bcd6 | DataField::Bcd8 | DataField::Bcd12,
+                Value::BcdError(digits),
+            ) if *digits >> (8 * length) == 0 => digits.to_le_bytes()[..length].to_vec(),
+            (DataField::VariableLength, Value::Text(text)) => {
+                // Text is transmitted last character first
+                let mut bytes = text
+                    .chars()
+                    .rev()
+                    .map(|c| u8::try_from(c).map_err(|_| ValueEncodeError::InvalidCharacter(c)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if bytes.len() > 0xBF {
+                    return Err(ValueEncodeError::InvalidLength(bytes.len()));
+                }
+
+                bytes.insert(0, bytes.len() as u8);
+                bytes
+            }
+            (DataField::VariableLength, Value::Bcd(value)) => {
+                let magnitude = value.unsigned_abs();
+                let length = (1..=9)
+                    .find(|&length| magnitude < 100u64.pow(length))
+                    .ok_or(ValueEncodeError::OutOfRange)? as usize;
+                let lvar = if *value < 0 { 0xD0 } else { 0xC0 };
+
+                let mut bytes = vec![lvar + length as u8];
+                bytes.extend(encode_bcd(magnitude as i64, length)?);
+                bytes
+            }
+            (DataField::VariableLength, Value::Binary(data)) => {
+                let lvar = match data.len() {
+                    length @ 0..=15 => 0xE0 + length as u8,
+                    length @ (16 | 20 | 24 | 28 | 32) => 0xEC + (length / 4) as u8,
+                    48 => 0xF5,
+                    64 => 0xF6,
+                    length => return Err(ValueEncodeError::InvalidLength(length)),
+                };
+
+                let mut bytes = vec![lvar];
+                bytes.extend_from_slice(data);
+                bytes
+            }
+            _ => return Err(ValueEncodeError::UnsupportedDataField(data_field.into())),
+        };
+
+        Ok(bytes)
+    }
+
+    /// Get the value as a number
+    ///
+    /// Returns `None` for values that aren't numbers.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(value) | Value::Bcd(value) => Some(*value as f64),
+            Value::Real(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// Get the value as text
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Value::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a date and time
+    ///
+    /// Dates are converted to midnight, and dates and times with time zone to
+    /// their local date and time. Returns `None` for values that aren't valid
+    /// dates, and for daylight saving periods, which have no year: see
+    /// [`DaylightSaving::to_naive_date_times`].
+    #[cfg(feature = "chrono")]
+    pub fn to_naive_date_time(&self) -> Option<chrono::NaiveDateTime> {
+        match self {
+            Value::Date(date) => date.to_naive_date()?.and_hms_opt(0, 0, 0),
+            Value::DateTime(date_time) => date_time.to_naive_date_time(),
+            Value::ZonedDateTime(zoned) => zoned.date_time.to_naive_date_time(),
+            _ => None,
+        }
+    }
+}
+
+/// Display the value, with dates in ISO 8601 format
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::None => Ok(()),
+            Value::Integer(value) | Value::Bcd(value) => write!(f, "{value}"),
+            Value::Real(value) => write!(f, "{value}"),
+            Value::BcdError(digits) => write!(f, "{digits:X}"),
+            Value::Date(date) => write!(f, "{date}"),
+            Value::Time(time) => write!(f, "{time}"),
+            Value::DateTime(date_time) => write!(f, "{date_time}"),
+            Value::Text(text) => f.write_str(text),
+            Value::ZonedDateTime(zoned) => write!(f, "{zoned}"),
+            Value::DaylightSaving(daylight_saving) => write!(f, "{daylight_saving}"),
+            Value::Binary(bytes) => write!(f, "{bytes:02X?}"),
+        }
+    }
+}
+
+/// Check whether the value information describes a date or time
+fn is_date_time(information: &ValueInformation) -> bool {
+    matches!(
+        information.quantity(),
+        Quantity::Date
+            | Quantity::DateTime
+            | Quantity::TariffStart
+            | Quantity::BatteryChangeDateTime
+    ) || information.modifiers().iter().any(|modifier| {
+        matches!(
+            modifier,
+            Modifier::StartDateTime | Modifier::LimitExceedDateTime(..) | Modifier::DateTime(..)
+        )
+    })
+}
+
+/// Check whether the value information describes a bit field (type D)
+fn is_bit_field(information: &ValueInformation) -> bool {
+    matches!(
+        information.quantity(),
+        Quantity::ErrorFlags | Quantity::ErrorMask | Quantity::DigitalInput | Quantity::DigitalOutput
+    )
+}
+
+/// Decode a signed little-endian integer of up to 8 bytes (type B)
+fn integer(data: &[u8]) -> i64 {
+    let mut bytes = [0; 8];
+    bytes[..data.len()].copy_from_slice(data);
+
+    // Sign extend from the most significant byte of the data
+    let shift = 64 - 8 * data.len() as u32;
+    (i64::from_le_bytes(bytes) << shift) >> shift
+}
+
+/// Decode a little-endian BCD value (type A)
+///
+/// A leading 0xF digit marks a negative value. Any other hex digit marks an
+/// error, and the raw digits are returned.
+fn bcd(data: &[u8]) -> Value {
+    let digits = data
+        .iter()
+        .rev()
+        .fold(0u128, |acc, &byte| (acc << 8) | byte as u128);
+    let count = data.len() * 2;
+
+    let msd = ((digits >> ((count - 1) * 4)) & 0xF) as u8;
+    let (negative, count) = if msd == BCD_NEGATIVE { (true, count - 1) } else { (false, count) };
+
+    let mut value = 0i64;
+    for index in (0..count).rev() {
+        match (digits >> (index * 4)) & 0xF {
+            digit @ 0..=9 => value = value * 10 + digit as i64,
+            _ => return Value::BcdError(digits),
+        }
+    }
+
+    Value::Bcd(if negative { -value } else { value })
+}
+
+/// Encode a little-endian integer, accepting both signed and unsigned values
+/// that fit the given number of bytes
+fn encode_integer(value: i64, length: usize) -> Result<Vec<u8>, ValueEncodeError> {
+    let bits = 8 * length as u32;
+    if bits < 64 && (value < -(1 << (bits - 1)) || value >= 1 << bits) {
+        return Err(ValueEncodeError::OutOfRange);
+    }
+
+    Ok(value.to_le_bytes()[..length].to_vec())
+}
+
+/// Encode a little-endian BCD value (type A) of the given number of bytes
+///
+/// Negative values give up their most significant digit to the sign digit.
+fn encode_bcd(value: i64, length: usize) -> Result<Vec<u8>, ValueEncodeError> {
+    let (mut magnitude, count) = match value < 0 {
+        true => (value.unsigned_abs(), 2 * length - 1),
+        false => (value as u64, 2 * length),
+    };
+
+    let mut digits = 0u128;
+    for index in 0..count {
+        digits |= ((magnitude % 10) as u128) << (4 * index);
+        magnitude /= 10;
+    }
+
+    if magnitude != 0 {
+        return Err(ValueEncodeError::OutOfRange);
+    }
+
+    if value < 0 {
+        digits |= (BCD_NEGATIVE as u128) << (4 * count);
+    }
+
+    Ok(digits.to_le_bytes()[..length].to_vec())
+}
+
+/// Get the length of variable length data from its LVAR byte
+///
+/// The length doesn't include the LVAR byte itself. Returns `None` for
+/// reserved LVAR values.
+pub(crate) fn lvar_length(lvar: u8) -> Option<usize> {
+    let length = match lvar {
+        0x00..=0xBF => lvar as usize,
+        0xC0..=0xC9 | 0xD0..=0xD9 => (lvar & 0x0F) as usize,
+        0xE0..=0xEF => (lvar - 0xE0) as usize,
+        0xF0..=0xF4 => 4 * (lvar - 0xEC) as usize,
+        0xF5 => 48,
+        0xF6 => 64,
+        _ => return None,
+    };
+
+    Some(length)
+}
+
+/// Decode variable length data, starting with its LVAR byte
+fn variable_length(data: &[u8]) -> Result<Value, ValueDecodeError> {
+    let (&lvar, data) = data.split_first().ok_or(ValueDecodeError::InvalidLength(1, 0))?;
+    let length = lvar_length(lvar).ok_or(ValueDecodeError::ReservedLvar(lvar))?;
+    if data.len() != length {
+        return Err(ValueDecodeError::InvalidLength(1 + length, 1 + data.len()));
+    }
+
+    let value = match lvar {
+        // Text is transmitted last character first
+        0x00..=0xBF => Value::Text(data.iter().rev().map(|&byte| byte as char).collect()),
+        0xC0..=0xC9 => match bcd_digits(data) {
+            Some(value) => Value::Bcd(value),
+            None => return Ok(bcd(data)),
+        },
+        0xD0..=0xD9 => match bcd_digits(data) {
+            Some(value) => Value::Bcd(-value),
+            None => return Ok(bcd(data)),
+        },
+        _ => Value::Binary(data.to_vec()),
+    };
+
+    Ok(value)
+}
+
+/// Decode little-endian BCD digits, without sign digit
+fn bcd_digits(data: &[u8]) -> Option<i64> {
+    data.iter().rev().try_fold(0i64, |acc, &byte| {
+        let (high, low) = (byte >> 4, byte & 0x0F);
+        (high <= 9 && low <= 9).then(|| acc * 100 + (high * 10 + low) as i64)
+    })
+}
+
+/// Date
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// Decode a date from its 2-byte encoding (type G)
+    fn from_type_g(data: &[u8]) -> Self {
+        Self::from_bytes(data[0], data[1], 0)
+    }
+
+    /// Decode the date bytes shared by types F, G and I
+    fn from_bytes(day: u8, month: u8, hundred_years: u8) -> Self {
+        let year = ((day & 0xE0) >> 5) | ((month & 0xF0) >> 1);
+
+        // Without hundred years, years up to 80 are in the 21st century
+        let year = match (hundred_years, year) {
+            (0, 0..=80) => 2000 + year as u16,
+            (hundred_years, year) => 1900 + 100 * hundred_years as u16 + year as u16,
+        };
+
+        Self {
+            year,
+            month: month & 0x0F,
+            day: day & 0x1F,
+        }
+    }
+
+    /// Encode a date into its 2-byte encoding (type G)
+    fn to_type_g(self) -> Result<[u8; 2], ValueEncodeError> {
+        match self.to_bytes()? {
+            (day, month, 0) => Ok([day, month]),
+            _ => Err(ValueEncodeError::OutOfRange),
+        }
+    }
+
+    /// Encode the date bytes shared by types F, G and I
+    ///
+    /// Returns the day and month bytes, and the hundred years.
+    fn to_bytes(self) -> Result<(u8, u8, u8), ValueEncodeError> {
+        let (hundred_years, year) = match self.year {
+            1981..=1999 => (0, self.year - 1900),
+            2000..=2080 => (0, self.year - 2000),
+            2081..=2299 => ((self.year - 1900) / 100, (self.year - 1900) % 100),
+            _ => return Err(ValueEncodeError::OutOfRange),
+        };
+
+        if self.month > 0x0F || self.day > 0x1F {
+            return Err(ValueEncodeError::OutOfRange);
+        }
+
+        let year = year as u8;
+        Ok((
+            self.day | (year & 0x07) << 5,
+            self.month | (year & 0x78) << 1,
+            hundred_years as u8,
+        ))
+    }
+
+    /// Convert the date to a chrono date
+    ///
+    /// Returns `None` if the date isn't valid.
+    #[cfg(feature = "chrono")]
+    pub fn to_naive_date(&self) -> Option<chrono::NaiveDate> {
+        chrono::NaiveDate::from_ymd_opt(self.year as i32, self.month as u32, self.day as u32)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Time
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl Time {
+    /// Decode a time from its 3-byte encoding (type J)
+    fn from_type_j(data: &[u8]) -> Self {
+        Self {
+            hour: data[2] & 0x1F,
+            minute: data[1] & 0x3F,
+            second: data[0] & 0x3F,
+        }
+    }
+
+    /// Encode a time into its 3-byte encoding (type J)
+    fn to_type_j(self) -> Result<[u8; 3], ValueEncodeError> {
+        if self.hour > 0x1F || self.minute > 0x3F || self.second > 0x3F {
+            return Err(ValueEncodeError::OutOfRange);
+        }
+
+        Ok([self.second, self.minute, self.hour])
+    }
+
+    /// Convert the time to a chrono time
+    ///
+    /// Returns `None` if the time isn't valid.
+    #[cfg(feature = "chrono")]
+    pub fn to_naive_time(&self) -> Option<chrono::NaiveTime> {
+        chrono::NaiveTime::from_hms_opt(self.hour as u32, self.minute as u32, self.second as u32)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+/// Date and time
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DateTime {
+    pub date: Date,
+    pub time: Time,
+
+    /// Whether the time is summer time
+    pub summer_time: bool,
+
+    /// Whether the slave flagged the date and time as invalid
+    pub invalid: bool,
+}
+
+impl DateTime {
+    /// Decode a date and time from its 4-byte encoding (type F)
+    fn from_type_f(data: &[u8]) -> Self {
+        Self {
+            date: Date::from_bytes(data[2], data[3], (data[1] & 0x60) >> 5),
+            time: Time {
+                hour: data[1] & 0x1F,
+                minute: data[0] & 0x3F,
+                second: 0,
+            },
+            summer_time: data[1] & 0x80 != 0,
+            invalid: data[0] & 0x80 != 0,
+        }
+    }
+
+    /// Decode a date and time from its 6-byte encoding (type I)
+    fn from_type_i(data: &[u8]) -> Self {
+        Self {
+            date: Date::from_bytes(data[3], data[4], 0),
+            time: Time {
+                hour: data[2] & 0x1F,
+                minute: data[1] & 0x3F,
+                second: data[0] & 0x3F,
+            },
+            summer_time: data[1] & 0x40 != 0,
+            invalid: data[1] & 0x80 != 0,
+        }
+    }
+
+    /// Encode a date and time into its 4-byte encoding (type F)
+    ///
+    /// Type F has no seconds, so the seconds must be zero.
+    fn to_type_f(self) -> Result<[u8; 4], ValueEncodeError> {
+        let [second, minute, hour] = self.time.to_type_j()?;
+        let (day, month, hundred_years) = self.date.to_bytes()?;
+        if second != 0 {
+            return Err(ValueEncodeError::OutOfRange);
+        }
+
+        Ok([
+            minute | (self.invalid as u8) << 7,
+            hour | hundred_years << 5 | (self.summer_time as u8) << 7,
+            day,
+            month,
+        ])
+    }
+
+    /// Encode a date and time into its 6-byte encoding (type I)
+    fn to_type_i(self) -> Result<[u8; 6], ValueEncodeError> {
+        let [second, minute, hour] = self.time.to_type_j()?;
+        let [day, month] = self.date.to_type_g()?;
+
+        Ok([
+            second,
+            minute | (self.summer_time as u8) << 6 | (self.invalid as u8) << 7,
+            hour,
+            day,
+            month,
+            0,
+        ])
+    }
+
+    /// Convert the date and time to a chrono date and time
+    ///
+    /// Returns `None` if the date and time isn't valid.
+    #[cfg(feature = "chrono")]
+    pub fn to_naive_date_time(&self) -> Option<chrono::NaiveDateTime> {
+        if self.invalid {
+            return None;
+        }
+
+        Some(self.date.to_naive_date()?.and_time(self.time.to_naive_time()?))
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}T{}", self.date, self.time)
+    }
+}
+
+/// Date and time with time zone
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ZonedDateTime {
+    /// Local date and time
+    pub date_time: DateTime,
+
+    /// Offset of the local time from UTC, in minutes
+    pub utc_offset: i16,
+}
+
+impl ZonedDateTime {
+    /// Decode a date and time with time zone from its 7-byte encoding
+    /// (type M), without the LVAR byte
+    ///
+    /// The date and time is encoded as type I, followed by the offset from
+    /// UTC in quarter hours (signed).
+    fn from_type_m(data: &[u8]) -> Self {
+        Self {
+            date_time: DateTime::from_type_i(&data[..6]),
+            utc_offset: 15 * (data[6] as i8) as i16,
+        }
+    }
+
+    /// Encode a date and time with time zone into its 7-byte encoding
+    /// (type M), without the LVAR byte
+    ///
+    /// The offset from UTC must be a whole number of quarter hours.
+    fn to_type_m(self) -> Result<[u8; 7], ValueEncodeError> {
+        let [second, minute, hour, day, month, year] = self.date_time.to_type_i()?;
+        let quarters = i8::try_from(self.utc_offset / 15).map_err(|_| ValueEncodeError::OutOfRange)?;
+        if self.utc_offset % 15 != 0 {
+            return Err(ValueEncodeError::OutOfRange);
+        }
+
+        Ok([second, minute, hour, day, month, year, quarters as u8])
+    }
+
+    /// Convert the date and time to a chrono date and time with time zone
+    ///
+    /// Returns `None` if the date and time isn't valid.
+    #[cfg(feature = "chrono")]
+    pub fn to_date_time(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let offset = chrono::FixedOffset::east_opt(60 * self.utc_offset as i32)?;
+        self.date_time.to_naive_date_time()?.and_local_timezone(offset).single()
+    }
+}
+
+/// Display the date and time in ISO 8601 format, with the offset from UTC
+impl fmt::Display for ZonedDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.utc_offset < 0 { '-' } else { '+' };
+        let offset = self.utc_offset.unsigned_abs();
+        write!(f, "{}{sign}{:02}:{:02}", self.date_time, offset / 60, offset % 60)
+    }
+}
+
+/// Begin or end of a daylight saving period
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DaylightSavingChange {
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+}
+
+impl fmt::Display for DaylightSavingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "--{:02}-{:02}T{:02}:00", self.month, self.day, self.hour)
+    }
+}
+
+/// Daylight saving period
+///
+/// The period repeats every year, so it has no year. The begin is in
+/// standard time and the end in daylight saving time, which are `deviation`
+/// hours apart.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DaylightSaving {
+    pub begin: DaylightSavingChange,
+    pub end: DaylightSavingChange,
+
+    /// Deviation of the daylight saving time from the standard time, in hours
+    pub deviation: i8,
+}
+
+impl DaylightSaving {
+    /// Decode a daylight saving period from its 4-byte encoding (type K)
+    ///
+    /// Only the begin hour is transmitted: the clock is set back at the same
+    /// hour of the standard time, so the end hour is the begin hour plus the
+    /// deviation.
+    fn from_type_k(data: &[u8]) -> Self {
+        let begin_hour = data[0] & 0x1F;
+        let deviation = (data[0] as i8) >> 6;
+
+        Self {
+            begin: DaylightSavingChange {
+                month: data[3] & 0x0F,
+                day: data[1] & 0x1F,
+                hour: begin_hour,
+            },
+            end: DaylightSavingChange {
+                month: data[3] >> 4,
+                day: data[2] & 0x1F,
+                hour: (begin_hour as i8 + deviation).rem_euclid(24) as u8,
+            },
+            deviation,
+        }
+    }
+
+    /// Encode a daylight saving period into its 4-byte encoding (type K)
+    ///
+    /// The end hour must be the begin hour plus the deviation, which must be
+    /// within -2 to 1 hours.
+    fn to_type_k(self) -> Result<[u8; 4], ValueEncodeError> {
+        let Self { begin, end, deviation } = self;
+        if !(-2..=1).contains(&deviation)
+            || begin.hour > 23
+            || end.hour as i8 != (begin.hour as i8 + deviation).rem_euclid(24)
+            || begin.month > 0x0F
+            || end.month > 0x0F
+            || begin.day > 0x1F
+            || end.day > 0x1F
+        {
+            return Err(ValueEncodeError::OutOfRange);
+        }
+
+        Ok([
+            begin.hour | (deviation as u8) << 6,
+            begin.day,
+            end.day,
+            begin.month | end.month << 4,
+        ])
+    }
+
+    /// Convert the daylight saving period of a year to chrono dates and times
+    ///
+    /// Returns the begin and end of the period, or `None` if they aren't
+    /// valid in that year.
+    #[cfg(feature = "chrono")]
+    pub fn to_naive_date_times(
+        &self,
+        year: i32,
+    ) -> Option<(chrono::NaiveDateTime, chrono::NaiveDateTime)> {
+        let convert = |change: DaylightSavingChange| {
+            chrono::NaiveDate::from_ymd_opt(year, change.month as u32, change.day as u32)?
+                .and_hms_opt(change.hour as u32, 0, 0)
+        };
+
+        Some((convert(self.begin)?, convert(self.end)?))
+    }
+}
+
+/// Display the period in ISO 8601 format, without year, with the deviation
+impl fmt::Display for DaylightSaving {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} {:+}h", self.begin, self.end, self.deviation)
+    }
+}
+
+/// Errors that can occur when decoding a value
+#[derive(Error, Debug)]
+pub enum ValueDecodeError {
+    #[error("invalid length for value, expected {0}, got {1}")]
+    InvalidLength(usize, usize),
+    #[error("reserved LVAR 0x{0:02X}")]
+    ReservedLvar(u8),
+    #[error("special functions don't carry a value")]
+    UnsupportedDataField,
+}
+
+/// Errors that can occur when encoding a value
+#[derive(Error, Debug)]
+pub enum ValueEncodeError {
+    #[error("value can't be encoded with data field 0x{0:X}")]
+    UnsupportedDataField(u8),
+    #[error("value out of range for data field")]
+    OutOfRange,
+    #[error("invalid length for variable length data: {0}")]
+    InvalidLength(usize),
+    #[error("character {0:?} can't be encoded as text")]
+    InvalidCharacter(char),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(data_field: DataField, vif: u8, data: &[u8]) -> Value {
+        Value::decode(data_field, &ValueInformation::from(vif), data).unwrap()
+    }
+
+    #[test]
+    fn it_decodes_signed_integers() {
+        assert_eq!(decode(DataField::Integer8, 0x13, &[0xFF]), Value::Integer(-1));
+        assert_eq!(decode(DataField::Integer16, 0x13, &[0x34, 0x12]), Value::Integer(0x1234));
+        assert_eq!(decode(DataField::Integer24, 0x13, &[0x00, 0x00, 0x80]), Value::Integer(-0x800000));
+        assert_eq!(
+            decode(DataField::Integer48, 0x13, &[0x01, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Value::Integer(1)
+        );
+        assert_eq!(decode(DataField::Integer64, 0x13, &[0xFF; 8]), Value::Integer(-1));
+    }
+
+    #[test]
+    fn it_decodes_a_real() {
+        let value = decode(DataField::Real32, 0x13, &1.5f32.to_le_bytes());
+        assert_eq!(value, Value::Real(1.5));
+        assert_eq!(value.as_f64(), Some(1.5));
+    }
+
+    #[test]
+    fn it_decodes_bcd_values() {
+        assert_eq!(decode(DataField::Bcd2, 0x13, &[0x42]), Value::Bcd(42));
+        assert_eq!(decode(DataField::Bcd8, 0x13, &[0x78, 0x56, 0x34, 0x12]), Value::Bcd(12345678));
+        assert_eq!(
+            decode(DataField::Bcd12, 0x13, &[0x12, 0x90, 0x78, 0x56, 0x34, 0x12]),
+            Value::Bcd(123456789012)
+        );
+    }
+
+    #[test]
+    fn it_decodes_a_negative_bcd_value() {
+        assert_eq!(decode(DataField::Bcd4, 0x13, &[0x23, 0xF1]), Value::Bcd(-123));
+    }
+
+    #[test]
+    fn it_keeps_the_raw_digits_of_a_bcd_value_with_error_markers() {
+        let value = decode(DataField::Bcd4, 0x13, &[0xAA, 0xAA]);
+        assert_eq!(value, Value::BcdError(0xAAAA));
+        assert_eq!(value.to_string(), "AAAA");
+        assert_eq!(value.as_f64(), None);
+    }
+
+    #[test]
+    fn it_decodes_a_type_g_date() {
+        // 2008-07-31
+        let value = decode(DataField::Integer16, 0x6C, &[0x1F, 0x17]);
+        assert_eq!(value, Value::Date(Date { year: 2008, month: 7, day: 31 }));
+        assert_eq!(value.to_string(), "2008-07-31");
+    }
+
+    #[test]
+    fn it_decodes_a_type_f_date_time() {
+        // 2008-05-31 23:50, summer time
+        let value = decode(DataField::Integer32, 0x6D, &[0x32, 0x97, 0x1F, 0x15]);
+        let Value::DateTime(date_time) = value else {
+            panic!("expected a date and time, got {value:?}");
+        };
+        assert_eq!(date_time.date, Date { year: 2008, month: 5, day: 31 });
+        assert_eq!(date_time.time, Time { hour: 23, minute: 50, second: 0 });
+        assert!(date_time.summer_time);
+        assert!(!date_time.invalid);
+    }
+
+    #[test]
+    fn it_decodes_a_type_i_date_time() {
+        let value = decode(DataField::Integer48, 0x6D, &[0x1E, 0x32, 0x17, 0x1F, 0x15, 0x00]);
+        assert_eq!(value.to_string(), "2008-05-31T23:50:30");
+    }
+
+    #[test]
+    fn it_decodes_a_type_j_time() {
+        let value = decode(DataField::Integer24, 0x6D, &[0x1E, 0x32, 0x17]);
+        assert_eq!(value, Value::Time(Time { hour: 23, minute: 50, second: 30 }));
+    }
+
+    #[test]
+    fn it_decodes_a_type_k_daylight_saving_period() {
+        // From the last Sunday of March 2024 at 02:00 to the last Sunday of
+        // October at 03:00, one hour ahead
+        let information = ValueInformation::decode(0xFD, &[0x72]);
+        let value = Value::decode(DataField::Integer32, &information, &[0x42, 0x1F, 0x1B, 0xA3]).unwrap();
+        let Value::DaylightSaving(daylight_saving) = value else {
+            panic!("expected a daylight saving period, got {value:?}");
+        };
+        assert_eq!(daylight_saving.begin, DaylightSavingChange { month: 3, day: 31, hour: 2 });
+        assert_eq!(daylight_saving.end, DaylightSavingChange { month: 10, day: 27, hour: 3 });
+        assert_eq!(daylight_saving.deviation, 1);
+        assert_eq!(daylight_saving.to_string(), "--03-31T02:00/--10-27T03:00 +1h");
+    }
+
+    #[test]
+    fn it_decodes_a_type_m_date_time_with_time_zone() {
+        let data = [0xE7, 0x1E, 0x32, 0x17, 0x1F, 0x15, 0x00, 0xF8];
+        let value = decode(DataField::VariableLength, 0x6D, &data);
+        let Value::ZonedDateTime(zoned) = value else {
+            panic!("expected a date and time with time zone, got {value:?}");
+        };
+        assert_eq!(zoned.date_time.date, Date { year: 2008, month: 5, day: 31 });
+        assert_eq!(zoned.utc_offset, -120);
+        assert_eq!(value.to_string(), "2008-05-31T23:50:30-02:00");
+    }
+
+    #[test]
+    fn it_decodes_variable_length_binary_data_without_date_time_information_as_binary() {
+        let data = [0xE7, 0x1E, 0x32, 0x17, 0x1F, 0x15, 0x00, 0xF8];
+        assert_eq!(decode(DataField::VariableLength, 0x13, &data), Value::Binary(data[1..].to_vec()));
+    }
+
+    #[test]
+    fn it_decodes_a_date_from_a_combinable_extension() {
+        let information = ValueInformation::decode(0x93, &[0x39]);
+        let value = Value::decode(DataField::Integer16, &information, &[0x1F, 0x17]).unwrap();
+        assert!(matches!(value, Value::Date(_)));
+    }
+
+    #[test]
+    fn it_decodes_bit_fields_as_binary() {
+        let information = ValueInformation::decode(0xFD, &[0x17]);
+        let value = Value::decode(DataField::Integer16, &information, &[0x80, 0x01]).unwrap();
+        assert_eq!(value, Value::Binary(vec![0x80, 0x01]));
+    }
+
+    #[test]
+    fn it_decodes_variable_length_data() {
+        assert_eq!(
+            decode(DataField::VariableLength, 0x13, &[0x03, 0x43, 0x42, 0x41]),
+            Value::Text(String::from("ABC"))
+        );
+        assert_eq!(
+            decode(DataField::VariableLength, 0x13, &[0xC2, 0x34, 0x12]),
+            Value::Bcd(1234)
+        );
+        assert_eq!(
+            decode(DataField::VariableLength, 0x13, &[0xD2, 0x34, 0x12]),
+            Value::Bcd(-1234)
+        );
+        assert_eq!(
+            decode(DataField::VariableLength, 0x13, &[0xE2, 0x34, 0x12]),
+            Value::Binary(vec![0x34, 0x12])
+        );
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_value_with_an_invalid_length() {
+        let err = Value::decode(DataField::Integer32, &ValueInformation::from(0x13), &[0x00]).unwrap_err();
+        assert!(matches!(err, ValueDecodeError::InvalidLength(4, 1)));
+    }
+
+    #[test]
+    fn it_keeps_the_raw_digits_of_an_18_digit_bcd_value_with_error_markers() {
+        let value = decode(DataField::VariableLength, 0x13, &[0xC9, 0xAA, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(value, Value::BcdError(0xAA));
+        let value = decode(DataField::VariableLength, 0x13, &[0xD9, 0, 0, 0, 0, 0, 0, 0, 0, 0xAA]);
+        assert_eq!(value, Value::BcdError(0xAA << 64));
+    }
+
+    #[test]
+    fn it_fails_to_decode_variable_length_data_not_matching_its_lvar() {
+        let information = ValueInformation::from(0x13);
+        let err = Value::decode(DataField::VariableLength, &information, &[0xC2]).unwrap_err();
+        assert!(matches!(err, ValueDecodeError::InvalidLength(3, 1)));
+        let err = Value::decode(DataField::VariableLength, &information, &[0x01, 0x41, 0x42]).unwrap_err();
+        assert!(matches!(err, ValueDecodeError::InvalidLength(2, 3)));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_reserved_lvar() {
+        let err = Value::decode(DataField::VariableLength, &ValueInformation::from(0x13), &[0xF7]).unwrap_err();
+        assert!(matches!(err, ValueDecodeError::ReservedLvar(0xF7)));
+    }
+
+    #[test]
+    fn it_round_trips_encoded_values() {
+        let cases: &[(DataField, u8, &[u8])] = &[
+            (DataField::NoData, 0x13, &[]),
+            (DataField::Integer8, 0x13, &[0xFF]),
+            (DataField::Integer24, 0x13, &[0x15, 0x31, 0x00]),
+            (DataField::Integer64, 0x13, &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x88]),
+            (DataField::Real32, 0x13, &[0x00, 0x00, 0xC0, 0x3F]),
+            (DataField::Bcd4, 0x13, &[0x23, 0xF1]),
+            (DataField::Bcd4, 0x13, &[0xAA, 0xAA]),
+            (DataField::Bcd12, 0x13, &[0x12, 0x90, 0x78, 0x56, 0x34, 0x12]),
+            (DataField::Integer16, 0x6C, &[0x1F, 0x17]),
+            (DataField::Integer24, 0x6D, &[0x1E, 0x32, 0x17]),
+            (DataField::Integer32, 0x6D, &[0xB2, 0x97, 0x1F, 0x15]),
+            (DataField::Integer48, 0x6D, &[0x1E, 0x72, 0x17, 0x1F, 0x15, 0x00]),
+            (DataField::VariableLength, 0x6D, &[0xE7, 0x1E, 0x32, 0x17, 0x1F, 0x15, 0x00, 0x04]),
+            (DataField::VariableLength, 0x13, &[0x03, 0x43, 0x42, 0x41]),
+            (DataField::VariableLength, 0x13, &[0xC2, 0x34, 0x12]),
+            (DataField::VariableLength, 0x13, &[0xD2, 0x34, 0x12]),
+            (DataField::VariableLength, 0x13, &[0xE2, 0x34, 0x12]),
+        ];
+
+        for &(data_field, vif, data) in cases {
+            let value = decode(data_field, vif, data);
+            assert_eq!(value.encode(data_field).unwrap(), data, "{value:?}");
+        }
+
+        let information = ValueInformation::decode(0xFD, &[0x72]);
+        for data in [[0x42, 0x1F, 0x1B, 0xA3], [0x80, 0x01, 0x02, 0x21]] {
+            let value = Value::decode(DataField::Integer32, &information, &data).unwrap();
+            assert_eq!(value.encode(DataField::Integer32).unwrap(), data, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn it_encodes_a_date_time_after_2080_with_hundred_years() {
+        let value = Value::DateTime(DateTime {
+            date: Date { year: 2099, month: 12, day: 31 },
+            time: Time { hour: 0, minute: 0, second: 0 },
+            summer_time: false,
+            invalid: false,
+        });
+
+        let data = value.encode(DataField::Integer32).unwrap();
+        assert_eq!(decode(DataField::Integer32, 0x6D, &data), value);
+    }
+
+    #[test]
+    fn it_fails_to_encode_a_value_that_does_not_fit() {
+        assert!(matches!(
+            Value::Integer(256).encode(DataField::Integer8),
+            Err(ValueEncodeError::OutOfRange)
+        ));
+        assert!(matches!(
+            Value::Bcd(-100).encode(DataField::Bcd2),
+            Err(ValueEncodeError::OutOfRange)
+        ));
+        assert!(matches!(
+            Value::Text(String::from("€")).encode(DataField::VariableLength),
+            Err(ValueEncodeError::InvalidCharacter('€'))
+        ));
+        let daylight_saving = DaylightSaving {
+            begin: DaylightSavingChange { month: 3, day: 31, hour: 2 },
+            end: DaylightSavingChange { month: 10, day: 27, hour: 2 },
+            deviation: 1,
+        };
+        assert!(matches!(
+            Value::DaylightSaving(daylight_saving).encode(DataField::Integer32),
+            Err(ValueEncodeError::OutOfRange)
+        ));
+        let zoned = ZonedDateTime {
+            date_time: DateTime {
+                date: Date { year: 2008, month: 5, day: 31 },
+                time: Time { hour: 23, minute: 50, second: 30 },
+                summer_time: false,
+                invalid: false,
+            },
+            utc_offset: 20,
+        };
+        assert!(matches!(
+            Value::ZonedDateTime(zoned).encode(DataField::VariableLength),
+            Err(ValueEncodeError::OutOfRange)
+        ));
+        assert!(matches!(
+            Value::Real(1.0).encode(DataField::Integer32),
+            Err(ValueEncodeError::UnsupportedDataField(0x4))
+        ));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn it_converts_a_date_time_to_chrono() {
+        let value = decode(DataField::Integer32, 0x6D, &[0x32, 0x97, 0x1F, 0x15]);
+        let expected = chrono::NaiveDate::from_ymd_opt(2008, 5, 31)
+            .unwrap()
+            .and_hms_opt(23, 50, 0)
+            .unwrap();
+        assert_eq!(value.to_naive_date_time(), Some(expected));
+
+        let invalid = decode(DataField::Integer32, 0x6D, &[0xB2, 0x97, 0x1F, 0x15]);
+        assert_eq!(invalid.to_naive_date_time(), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn it_converts_a_date_time_with_time_zone_to_chrono() {
+        let value = decode(DataField::VariableLength, 0x6D, &[0xE7, 0x1E, 0x32, 0x17, 0x1F, 0x15, 0x00, 0x04]);
+        let expected = chrono::NaiveDate::from_ymd_opt(2008, 5, 31)
+            .unwrap()
+            .and_hms_opt(23, 50, 30)
+            .unwrap();
+        assert_eq!(value.to_naive_date_time(), Some(expected));
+
+        let Value::ZonedDateTime(zoned) = value else {
+            panic!("expected a date and time with time zone, got {value:?}");
+        };
+        let date_time = zoned.to_date_time().unwrap();
+        assert_eq!(date_time.naive_utc(), expected - chrono::TimeDelta::hours(1));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn it_converts_a_daylight_saving_period_to_chrono() {
+        let information = ValueInformation::decode(0xFD, &[0x72]);
+        let value = Value::decode(DataField::Integer32, &information, &[0x42, 0x1F, 0x1B, 0xA3]).unwrap();
+        assert_eq!(value.to_naive_date_time(), None);
+
+        let Value::DaylightSaving(daylight_saving) = value else {
+            panic!("expected a daylight saving period, got {value:?}");
+        };
+        let date = |month, day, hour| {
+            chrono::NaiveDate::from_ymd_opt(2024, month, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+        };
+        assert_eq!(daylight_saving.to_naive_date_times(2024), Some((date(3, 31, 2), date(10, 27, 3))));
+    }
+}
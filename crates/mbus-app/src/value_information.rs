@@ -0,0 +1,779 @@
+/// VIF selecting the first extension table (0xFB)
+const FIRST_EXTENSION_VIF: u8 = 0xFB;
+
+/// VIF selecting the main extension table (0xFD)
+const MAIN_EXTENSION_VIF: u8 = 0xFD;
+
+/// VIF and VIFE announcing manufacturer specific VIFEs
+const MANUFACTURER_SPECIFIC: u8 = 0x7F;
+
+/// M-Bus Value Information
+///
+/// The value information describes what the value of a data record means:
+/// the physical quantity it measures, its unit, and the power of ten it must
+/// be multiplied by. It is decoded from the VIF, using the primary VIF table
+/// defined in EN 13757-3 (Table 10), or the extension tables selected by the
+/// 0xFB and 0xFD VIFs (Tables 12 and 14).
+///
+/// The VIFEs following the VIF are combinable (orthogonal) extensions, as
+/// defined in EN 13757-3 (Table 15). They refine the meaning of the value,
+/// e.g. a rate "per hour" or a limit value, and multiplicative corrections
+/// are folded into the exponent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueInformation {
+    /// Quantity measured by the value
+    quantity: Quantity,
+
+    /// Unit of the value
+    unit: Unit,
+
+    /// Power of ten the value must be multiplied by
+    exponent: i8,
+
+    /// Combinable extensions refining the meaning of the value
+    modifiers: Vec<Modifier>,
+}
+
+impl ValueInformation {
+    /// Create a new value information
+    pub fn new(quantity: Quantity, unit: Unit, exponent: i8) -> Self {
+        Self {
+            quantity,
+            unit,
+            exponent,
+            modifiers: Vec::new(),
+        }
+    }
+
+    /// Decode the value information from a VIF and its VIFE chain
+    ///
+    /// For the 0xFB and 0xFD VIFs, the first VIFE selects the entry of the
+    /// extension table. The remaining VIFEs are decoded as combinable
+    /// extensions, up to a manufacturer specific VIFE (0xFF), after which the
+    /// VIFEs aren't interpreted.
+    pub fn decode(vif: u8, vife: &[u8]) -> Self {
+        let (mut information, extensions) = match (vif, vife.split_first()) {
+            (FIRST_EXTENSION_VIF, Some((&code, rest))) => (first_extension(code), rest),
+            (MAIN_EXTENSION_VIF, Some((&code, rest))) => (main_extension(code), rest),
+            // The VIFEs of a manufacturer specific VIF are manufacturer
+            // specific as well
+            (0xFF, _) => (Self::from(vif), &[][..]),
+            _ => (Self::from(vif), vife),
+        };
+
+        for &vife in extensions {
+            let modifier = Modifier::from(vife);
+            if let Modifier::MultiplicativeCorrection(exponent) = modifier {
+                information.exponent += exponent;
+            }
+
+            information.modifiers.push(modifier);
+            if modifier == Modifier::ManufacturerSpecific {
+                break;
+            }
+        }
+
+        information
+    }
+
+    /// Get the quantity measured by the value
+    pub fn quantity(&self) -> Quantity {
+        self.quantity
+    }
+
+    /// Get the unit of the value
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// Get the power of ten the value must be multiplied by
+    ///
+    /// Includes the multiplicative corrections of the VIFE chain.
+    pub fn exponent(&self) -> i8 {
+        self.exponent
+    }
+
+    /// Get the combinable extensions refining the meaning of the value
+    pub fn modifiers(&self) -> &[Modifier] {
+        &self.modifiers
+    }
+
+    /// Scale a raw value to the unit of the value information
+    pub fn scale(&self, value: f64) -> f64 {
+        // Dividing keeps decimal values such as 12.565 exact, where
+        // multiplying by 0.001 wouldn't
+        if self.exponent < 0 {
+            value / 10f64.powi(-self.exponent as i32)
+        } else {
+            value * 10f64.powi(self.exponent as i32)
+        }
+    }
+}
+
+/// Implement conversion from a primary VIF to ValueInformation
+///
+/// The extension bit of the VIF is ignored.
+impl From<u8> for ValueInformation {
+    fn from(vif: u8) -> Self {
+        let vif = vif & 0x7F;
+        let n = (vif & 0x07) as i8;
+        let nn = (vif & 0x03) as i8;
+
+        match vif {
+            0x00..=0x07 => Self::new(Quantity::Energy, Unit::WattHour, n - 3),
+            0x08..=0x0F => Self::new(Quantity::Energy, Unit::Joule, n),
+            0x10..=0x17 => Self::new(Quantity::Volume, Unit::CubicMeter, n - 6),
+            0x18..=0x1F => Self::new(Quantity::Mass, Unit::Kilogram, n - 3),
+            0x20..=0x23 => Self::new(Quantity::OnTime, duration_unit(vif), 0),
+            0x24..=0x27 => Self::new(Quantity::OperatingTime, duration_unit(vif), 0),
+            0x28..=0x2F => Self::new(Quantity::Power, Unit::Watt, n - 3),
+            0x30..=0x37 => Self::new(Quantity::Power, Unit::JoulePerHour, n),
+            0x38..=0x3F => Self::new(Quantity::VolumeFlow, Unit::CubicMeterPerHour, n - 6),
+            0x40..=0x47 => Self::new(Quantity::VolumeFlow, Unit::CubicMeterPerMinute, n - 7),
+            0x48..=0x4F => Self::new(Quantity::VolumeFlow, Unit::CubicMeterPerSecond, n - 9),
+            0x50..=0x57 => Self::new(Quantity::MassFlow, Unit::KilogramPerHour, n - 3),
+            0x58..=0x5B => Self::new(Quantity::FlowTemperature, Unit::Celsius, nn - 3),
+            0x5C..=0x5F => Self::new(Quantity::ReturnTemperature, Unit::Celsius, nn - 3),
+            0x60..=0x63 => Self::new(Quantity::TemperatureDifference, Unit::Kelvin, nn - 3),
+            0x64..=0x67 => Self::new(Quantity::ExternalTemperature, Unit::Celsius, nn - 3),
+            0x68..=0x6B => Self::new(Quantity::Pressure, Unit::Bar, nn - 3),
+            0x6C => Self::new(Quantity::Date, Unit::None, 0),
+            0x6D => Self::new(Quantity::DateTime, Unit::None, 0),
+            0x6E => Self::new(Quantity::HeatCostAllocation, Unit::None, 0),
+            0x70..=0x73 => Self::new(Quantity::AveragingDuration, duration_unit(vif), 0),
+            0x74..=0x77 => Self::new(Quantity::ActualityDuration, duration_unit(vif), 0),
+            0x78 => Self::new(Quantity::FabricationNumber, Unit::None, 0),
+            0x79 => Self::new(Quantity::EnhancedIdentification, Unit::None, 0),
+            0x7A => Self::new(Quantity::BusAddress, Unit::None, 0),
+            0x7B | 0x7D => Self::new(Quantity::Extension(vif | 0x80), Unit::None, 0),
+            0x7C => Self::new(Quantity::PlainText, Unit::None, 0),
+            0x7E => Self::new(Quantity::Any, Unit::None, 0),
+            MANUFACTURER_SPECIFIC => Self::new(Quantity::ManufacturerSpecific, Unit::None, 0),
+            _ => Self::new(Quantity::Reserved(vif), Unit::None, 0),
+        }
+    }
+}
+
+/// Decode an entry of the first extension table (VIF 0xFB)
+///
+/// Units with a prefix (e.g. MWh) are converted to the base unit, and the
+/// prefix folded into the exponent.
+fn first_extension(vife: u8) -> ValueInformation {
+    let code = vife & 0x7F;
+    let n = (code & 0x01) as i8;
+    let nn = (code & 0x03) as i8;
+    let nnn = (code & 0x07) as i8;
+
+    match code {
+        0x00..=0x01 => ValueInformation::new(Quantity::Energy, Unit::WattHour, n + 5),
+        0x02..=0x03 => ValueInformation::new(Quantity::ReactiveEnergy, Unit::VarHour, n + 3),
+        0x08..=0x09 => ValueInformation::new(Quantity::Energy, Unit::Joule, n + 8),
+        0x0C..=0x0F => ValueInformation::new(Quantity::Energy, Unit::Calorie, nn + 5),
+        0x10..=0x11 => ValueInformation::new(Quantity::Volume, Unit::CubicMeter, n + 2),
+        0x14..=0x17 => ValueInformation::new(Quantity::ReactivePower, Unit::Var, nn),
+        0x18..=0x19 => ValueInformation::new(Quantity::Mass, Unit::Kilogram, n + 5),
+        0x1A..=0x1B => ValueInformation::new(Quantity::RelativeHumidity, Unit::Percent, n - 1),
+        0x20 => ValueInformation::new(Quantity::Volume, Unit::CubicFoot, 0),
+        0x21 => ValueInformation::new(Quantity::Volume, Unit::CubicFoot, -1),
+        0x28..=0x29 => ValueInformation::new(Quantity::Power, Unit::Watt, n + 5),
+        0x2A => ValueInformation::new(Quantity::PhaseVoltageVoltage, Unit::Degree, -1),
+        0x2B => ValueInformation::new(Quantity::PhaseVoltageCurrent, Unit::Degree, -1),
+        0x2C..=0x2F => ValueInformation::new(Quantity::Frequency, Unit::Hertz, nn - 3),
+        0x30..=0x31 => ValueInformation::new(Quantity::Power, Unit::JoulePerHour, n + 8),
+        0x34..=0x37 => ValueInformation::new(Quantity::ApparentPower, Unit::VoltAmpere, nn),
+        0x58..=0x5B => ValueInformation::new(Quantity::FlowTemperature, Unit::Fahrenheit, nn - 3),
+        0x5C..=0x5F => ValueInformation::new(Quantity::ReturnTemperature, Unit::Fahrenheit, nn - 3),
+        0x60..=0x63 => ValueInformation::new(Quantity::TemperatureDifference, Unit::Fahrenheit, nn - 3),
+        0x64..=0x67 => ValueInformation::new(Quantity::ExternalTemperature, Unit::Fahrenheit, nn - 3),
+        0x70..=0x73 => ValueInformation::new(Quantity::TemperatureLimit, Unit::Fahrenheit, nn - 3),
+        0x74..=0x77 => ValueInformation::new(Quantity::TemperatureLimit, Unit::Celsius, nn - 3),
+        0x78..=0x7F => ValueInformation::new(Quantity::CumulativeMaxPower, Unit::Watt, nnn - 3),
+        _ => ValueInformation::new(Quantity::ReservedExtension(FIRST_EXTENSION_VIF, code), Unit::None, 0),
+    }
+}
+
+/// Decode an entry of the main extension table (VIF 0xFD)
+fn main_extension(vife: u8) -> ValueInformation {
+    let code = vife & 0x7F;
+    let nn = (code & 0x03) as i8;
+    let nnnn = (code & 0x0F) as i8;
+
+    let (quantity, unit, exponent) = match code {
+        0x00..=0x03 => (Quantity::Credit, Unit::Currency, nn - 3),
+        0x04..=0x07 => (Quantity::Debit, Unit::Currency, nn - 3),
+        0x08 => (Quantity::AccessNumber, Unit::None, 0),
+        0x09 => (Quantity::Medium, Unit::None, 0),
+        0x0A => (Quantity::Manufacturer, Unit::None, 0),
+        0x0B => (Quantity::ParameterSetIdentification, Unit::None, 0),
+        0x0C => (Quantity::ModelVersion, Unit::None, 0),
+        0x0D => (Quantity::HardwareVersion, Unit::None, 0),
+        0x0E => (Quantity::FirmwareVersion, Unit::None, 0),
+        0x0F => (Quantity::SoftwareVersion, Unit::None, 0),
+        0x10 => (Quantity::CustomerLocation, Unit::None, 0),
+        0x11 => (Quantity::Customer, Unit::None, 0),
+        0x12 => (Quantity::AccessCodeUser, Unit::None, 0),
+        0x13 => (Quantity::AccessCodeOperator, Unit::None, 0),
+        0x14 => (Quantity::AccessCodeSystemOperator, Unit::None, 0),
+        0x15 => (Quantity::AccessCodeDeveloper, Unit::None, 0),
+        0x16 => (Quantity::Password, Unit::None, 0),
+        0x17 => (Quantity::ErrorFlags, Unit::None, 0),
+        0x18 => (Quantity::ErrorMask, Unit::None, 0),
+        0x1A => (Quantity::DigitalOutput, Unit::None, 0),
+        0x1B => (Quantity::DigitalInput, Unit::None, 0),
+        0x1C => (Quantity::BaudRate, Unit::Baud, 0),
+        0x1D => (Quantity::ResponseDelayTime, Unit::BitTime, 0),
+        0x1E => (Quantity::Retry, Unit::None, 0),
+        0x1F => (Quantity::RemoteControl, Unit::None, 0),
+        0x20 => (Quantity::FirstStorageNumber, Unit::None, 0),
+        0x21 => (Quantity::LastStorageNumber, Unit::None, 0),
+        0x22 => (Quantity::StorageBlockSize, Unit::None, 0),
+        0x24..=0x27 => (Quantity::StorageInterval, duration_unit(code), 0),
+        0x28 => (Quantity::StorageInterval, Unit::Month, 0),
+        0x29 => (Quantity::StorageInterval, Unit::Year, 0),
+        0x2C..=0x2F => (Quantity::DurationSinceLastReadout, duration_unit(code), 0),
+        0x30 => (Quantity::TariffStart, Unit::None, 0),
+        0x31..=0x33 => (Quantity::TariffDuration, duration_unit(code), 0),
+        0x34..=0x37 => (Quantity::TariffPeriod, duration_unit(code), 0),
+        0x38 => (Quantity::TariffPeriod, Unit::Month, 0),
+        0x39 => (Quantity::TariffPeriod, Unit::Year, 0),
+        0x3A => (Quantity::Dimensionless, Unit::None, 0),
+        0x40..=0x4F => (Quantity::Voltage, Unit::Volt, nnnn - 9),
+        0x50..=0x5F => (Quantity::Current, Unit::Ampere, nnnn - 12),
+        0x60 => (Quantity::ResetCounter, Unit::None, 0),
+        0x61 => (Quantity::CumulationCounter, Unit::None, 0),
+        0x62 => (Quantity::ControlSignal, Unit::None, 0),
+        0x63 => (Quantity::DayOfWeek, Unit::None, 0),
+        0x64 => (Quantity::WeekNumber, Unit::None, 0),
+        0x65 => (Quantity::DayChangeTimePoint, Unit::None, 0),
+        0x66 => (Quantity::ParameterActivationState, Unit::None, 0),
+        0x67 => (Quantity::SpecialSupplierInformation, Unit::None, 0),
+        0x68..=0x6B => (Quantity::DurationSinceLastCumulation, long_duration_unit(code), 0),
+        0x6C..=0x6F => (Quantity::BatteryOperatingTime, long_duration_unit(code), 0),
+        0x70 => (Quantity::BatteryChangeDateTime, Unit::None, 0),
+        0x71 => (Quantity::RfLevel, Unit::DecibelMilliwatt, 0),
+        0x72 => (Quantity::DaylightSaving, Unit::None, 0),
+        0x73 => (Quantity::ListeningWindowManagement, Unit::None, 0),
+        0x74 => (Quantity::RemainingBatteryLifetime, Unit::Day, 0),
+        0x75 => (Quantity::MeterStopCount, Unit::None, 0),
+        0x76 => (Quantity::ManufacturerDataContainer, Unit::None, 0),
+        _ => (Quantity::ReservedExtension(MAIN_EXTENSION_VIF, code), Unit::None, 0),
+    };
+
+    ValueInformation::new(quantity, unit, exponent)
+}
+
+/// Get the unit of a duration, from the last two bits of its VIF
+fn duration_unit(vif: u8) -> Unit {
+    match vif & 0x03 {
+        0x0 => Unit::Second,
+        0x1 => Unit::Minute,
+        0x2 => Unit::Hour,
+        _ => Unit::Day,
+    }
+}
+
+/// Get the unit of a long duration, from the last two bits of its VIFE
+fn long_duration_unit(vife: u8) -> Unit {
+    match vife & 0x03 {
+        0x0 => Unit::Hour,
+        0x1 => Unit::Day,
+        0x2 => Unit::Month,
+        _ => Unit::Year,
+    }
+}
+
+/// Quantity measured by the value of a data record
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Quantity {
+    Energy,
+    Volume,
+    Mass,
+    OnTime,
+    OperatingTime,
+    Power,
+    VolumeFlow,
+    MassFlow,
+    FlowTemperature,
+    ReturnTemperature,
+    TemperatureDifference,
+    ExternalTemperature,
+    Pressure,
+    /// Date (type G)
+    Date,
+    /// Date and time (type F)
+    DateTime,
+    /// Units for heat cost allocators, without dimension
+    HeatCostAllocation,
+    AveragingDuration,
+    ActualityDuration,
+    FabricationNumber,
+    EnhancedIdentification,
+    BusAddress,
+    /// Unit given as plain text, following the VIF
+    PlainText,
+    /// Any VIF, used in readout requests
+    Any,
+    ManufacturerSpecific,
+    /// Extended VIF table (0xFB or 0xFD), without the VIFE selecting the entry
+    Extension(u8),
+    Reserved(u8),
+
+    // First extension table (0xFB)
+    ReactiveEnergy,
+    ReactivePower,
+    RelativeHumidity,
+    PhaseVoltageVoltage,
+    PhaseVoltageCurrent,
+    Frequency,
+    ApparentPower,
+    /// Cold / warm temperature limit
+    TemperatureLimit,
+    /// Cumulative count of maximum power
+    CumulativeMaxPower,
+
+    // Main extension table (0xFD)
+    Credit,
+    Debit,
+    AccessNumber,
+    Medium,
+    Manufacturer,
+    ParameterSetIdentification,
+    ModelVersion,
+    HardwareVersion,
+    FirmwareVersion,
+    SoftwareVersion,
+    CustomerLocation,
+    Customer,
+    AccessCodeUser,
+    AccessCodeOperator,
+    AccessCodeSystemOperator,
+    AccessCodeDeveloper,
+    Password,
+    ErrorFlags,
+    ErrorMask,
+    DigitalOutput,
+    DigitalInput,
+    BaudRate,
+    ResponseDelayTime,
+    Retry,
+    RemoteControl,
+    FirstStorageNumber,
+    LastStorageNumber,
+    StorageBlockSize,
+    StorageInterval,
+    DurationSinceLastReadout,
+    TariffStart,
+    TariffDuration,
+    TariffPeriod,
+    Dimensionless,
+    Voltage,
+    Current,
+    ResetCounter,
+    CumulationCounter,
+    ControlSignal,
+    DayOfWeek,
+    WeekNumber,
+    DayChangeTimePoint,
+    ParameterActivationState,
+    SpecialSupplierInformation,
+    DurationSinceLastCumulation,
+    BatteryOperatingTime,
+    BatteryChangeDateTime,
+    RfLevel,
+    DaylightSaving,
+    ListeningWindowManagement,
+    RemainingBatteryLifetime,
+    MeterStopCount,
+    ManufacturerDataContainer,
+
+    /// Reserved entry of an extension table, with the VIF of the table and
+    /// the code of the entry
+    ReservedExtension(u8, u8),
+}
+
+/// Unit of the value of a data record
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Unit {
+    WattHour,
+    Joule,
+    CubicMeter,
+    Kilogram,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Year,
+    Watt,
+    JoulePerHour,
+    CubicMeterPerHour,
+    CubicMeterPerMinute,
+    CubicMeterPerSecond,
+    KilogramPerHour,
+    Celsius,
+    Kelvin,
+    Fahrenheit,
+    Bar,
+    VarHour,
+    Var,
+    VoltAmpere,
+    Calorie,
+    Percent,
+    CubicFoot,
+    Degree,
+    Hertz,
+    Volt,
+    Ampere,
+    /// Nominal local legal currency units
+    Currency,
+    Baud,
+    BitTime,
+    DecibelMilliwatt,
+    None,
+}
+
+impl Unit {
+    /// Get the symbol of the unit
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::WattHour => "Wh",
+            Unit::Joule => "J",
+            Unit::CubicMeter => "m³",
+            Unit::Kilogram => "kg",
+            Unit::Second => "s",
+            Unit::Minute => "min",
+            Unit::Hour => "h",
+            Unit::Day => "d",
+            Unit::Month => "month",
+            Unit::Year => "a",
+            Unit::Watt => "W",
+            Unit::JoulePerHour => "J/h",
+            Unit::CubicMeterPerHour => "m³/h",
+            Unit::CubicMeterPerMinute => "m³/min",
+            Unit::CubicMeterPerSecond => "m³/s",
+            Unit::KilogramPerHour => "kg/h",
+            Unit::Celsius => "°C",
+            Unit::Kelvin => "K",
+            Unit::Fahrenheit => "°F",
+            Unit::Bar => "bar",
+            Unit::VarHour => "varh",
+            Unit::Var => "var",
+            Unit::VoltAmpere => "VA",
+            Unit::Calorie => "cal",
+            Unit::Percent => "%",
+            Unit::CubicFoot => "ft³",
+            Unit::Degree => "°",
+            Unit::Hertz => "Hz",
+            Unit::Volt => "V",
+            Unit::Ampere => "A",
+            Unit::Currency => "¤",
+            Unit::Baud => "Bd",
+            Unit::BitTime => "bit times",
+            Unit::DecibelMilliwatt => "dBm",
+            Unit::None => "",
+        }
+    }
+}
+
+/// Limit referred to by a combinable extension
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Limit {
+    Lower,
+    Upper,
+}
+
+/// Occurrence referred to by a combinable extension
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Occurrence {
+    First,
+    Last,
+}
+
+/// Boundary of a period referred to by a combinable extension
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Boundary {
+    Begin,
+    End,
+}
+
+/// Combinable (orthogonal) VIFE
+///
+/// Combinable VIFEs refine the meaning of the value described by the VIF, as
+/// defined in EN 13757-3 (Table 15).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Modifier {
+    /// Record error code in slave replies, or object action in master
+    /// requests (0x00 - 0x1F)
+    ErrorOrAction(u8),
+    PerSecond,
+    PerMinute,
+    PerHour,
+    PerDay,
+    PerWeek,
+    PerMonth,
+    PerYear,
+    PerRevolution,
+    /// Increment per input pulse on the given input channel
+    PerInputPulse(u8),
+    /// Increment per output pulse on the given output channel
+    PerOutputPulse(u8),
+    PerLiter,
+    PerCubicMeter,
+    PerKilogram,
+    PerKelvin,
+    PerKilowattHour,
+    PerGigajoule,
+    PerKilowatt,
+    PerKelvinLiter,
+    PerVolt,
+    PerAmpere,
+    MultipliedBySecond,
+    MultipliedBySecondPerVolt,
+    MultipliedBySecondPerAmpere,
+    /// Start date (and time) of the value
+    StartDateTime,
+    /// The VIF contains the uncorrected unit, instead of the corrected one
+    UncorrectedUnit,
+    /// Accumulation only if positive contributions
+    AccumulationPositive,
+    /// Accumulation of the absolute value only if negative contributions
+    AccumulationAbsoluteNegative,
+    ValueAtBaseConditions,
+    ObisDeclaration,
+    LimitValue(Limit),
+    /// Number of times the limit was exceeded
+    LimitExceedCount(Limit),
+    /// Date (and time) of the given occurrence of the limit being exceeded
+    LimitExceedDateTime(Limit, Occurrence, Boundary),
+    /// Duration of the given occurrence of the limit being exceeded
+    LimitExceedDuration(Limit, Occurrence, Unit),
+    /// Duration of the given occurrence
+    Duration(Occurrence, Unit),
+    /// Value during the limit being exceeded
+    ValueDuringLimitExceed(Limit),
+    LeakageValues,
+    OverflowValues,
+    /// Date (and time) of the given occurrence
+    DateTime(Occurrence, Boundary),
+    /// Multiplicative correction factor, as a power of ten
+    MultiplicativeCorrection(i8),
+    /// Additive correction constant, as a power of ten of the unit of the VIF
+    AdditiveCorrection(i8),
+    FutureValue,
+    /// The following VIFEs and the data are manufacturer specific
+    ManufacturerSpecific,
+    Reserved(u8),
+}
+
+/// Implement conversion from a VIFE to Modifier
+///
+/// The extension bit of the VIFE is ignored.
+impl From<u8> for Modifier {
+    fn from(vife: u8) -> Self {
+        let code = vife & 0x7F;
+        let limit = if code & 0x08 == 0 { Limit::Lower } else { Limit::Upper };
+        let occurrence = if code & 0x04 == 0 { Occurrence::First } else { Occurrence::Last };
+        let boundary = if code & 0x01 == 0 { Boundary::Begin } else { Boundary::End };
+
+        match code {
+            0x00..=0x1F => Modifier::ErrorOrAction(code),
+            0x20 => Modifier::PerSecond,
+            0x21 => Modifier::PerMinute,
+            0x22 => Modifier::PerHour,
+            0x23 => Modifier::PerDay,
+            0x24 => Modifier::PerWeek,
+            0x25 => Modifier::PerMonth,
+            0x26 => Modifier::PerYear,
+            0x27 => Modifier::PerRevolution,
+            0x28..=0x29 => Modifier::PerInputPulse(code & 0x01),
+            0x2A..=0x2B => Modifier::PerOutputPulse(code & 0x01),
+            0x2C => Modifier::PerLiter,
+            0x2D => Modifier::PerCubicMeter,
+            0x2E => Modifier::PerKilogram,
+            0x2F => Modifier::PerKelvin,
+            0x30 => Modifier::PerKilowattHour,
+            0x31 => Modifier::PerGigajoule,
+            0x32 => Modifier::PerKilowatt,
+            0x33 => Modifier::PerKelvinLiter,
+            0x34 => Modifier::PerVolt,
+            0x35 => Modifier::PerAmpere,
+            0x36 => Modifier::MultipliedBySecond,
+            0x37 => Modifier::MultipliedBySecondPerVolt,
+            0x38 => Modifier::MultipliedBySecondPerAmpere,
+            0x39 => Modifier::StartDateTime,
+            0x3A => Modifier::UncorrectedUnit,
+            0x3B => Modifier::AccumulationPositive,
+            0x3C => Modifier::AccumulationAbsoluteNegative,
+            0x3E => Modifier::ValueAtBaseConditions,
+            0x3F => Modifier::ObisDeclaration,
+            0x40 | 0x48 => Modifier::LimitValue(limit),
+            0x41 | 0x49 => Modifier::LimitExceedCount(limit),
+            0x42 | 0x43 | 0x46 | 0x47 | 0x4A | 0x4B | 0x4E | 0x4F => {
+                Modifier::LimitExceedDateTime(limit, occurrence, boundary)
+            }
+            0x50..=0x5F => Modifier::LimitExceedDuration(limit, occurrence, duration_unit(code)),
+            0x60..=0x67 => Modifier::Duration(occurrence, duration_unit(code)),
+            0x68 | 0x6C => Modifier::ValueDuringLimitExceed(if code & 0x04 == 0 {
+                Limit::Lower
+            } else {
+                Limit::Upper
+            }),
+            0x69 => Modifier::LeakageValues,
+            0x6D => Modifier::OverflowValues,
+            0x6A | 0x6B | 0x6E | 0x6F => Modifier::DateTime(occurrence, boundary),
+            0x70..=0x77 => Modifier::MultiplicativeCorrection((code & 0x07) as i8 - 6),
+            0x78..=0x7B => Modifier::AdditiveCorrection((code & 0x03) as i8 - 3),
+            0x7D => Modifier::MultiplicativeCorrection(3),
+            0x7E => Modifier::FutureValue,
+            MANUFACTURER_SPECIFIC => Modifier::ManufacturerSpecific,
+            _ => Modifier::Reserved(code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_decodes_energy() {
+        assert_eq!(ValueInformation::from(0x04), ValueInformation::new(Quantity::Energy, Unit::WattHour, 1));
+        assert_eq!(ValueInformation::from(0x0E), ValueInformation::new(Quantity::Energy, Unit::Joule, 6));
+    }
+
+    #[test]
+    fn it_decodes_volume_and_flow() {
+        assert_eq!(ValueInformation::from(0x13), ValueInformation::new(Quantity::Volume, Unit::CubicMeter, -3));
+        assert_eq!(
+            ValueInformation::from(0x3B),
+            ValueInformation::new(Quantity::VolumeFlow, Unit::CubicMeterPerHour, -3)
+        );
+        assert_eq!(
+            ValueInformation::from(0x4F),
+            ValueInformation::new(Quantity::VolumeFlow, Unit::CubicMeterPerSecond, -2)
+        );
+    }
+
+    #[test]
+    fn it_decodes_temperatures_and_pressure() {
+        assert_eq!(
+            ValueInformation::from(0x5A),
+            ValueInformation::new(Quantity::FlowTemperature, Unit::Celsius, -1)
+        );
+        assert_eq!(
+            ValueInformation::from(0x61),
+            ValueInformation::new(Quantity::TemperatureDifference, Unit::Kelvin, -2)
+        );
+        assert_eq!(ValueInformation::from(0x6B), ValueInformation::new(Quantity::Pressure, Unit::Bar, 0));
+    }
+
+    #[test]
+    fn it_decodes_durations() {
+        assert_eq!(ValueInformation::from(0x22), ValueInformation::new(Quantity::OnTime, Unit::Hour, 0));
+        assert_eq!(
+            ValueInformation::from(0x74),
+            ValueInformation::new(Quantity::ActualityDuration, Unit::Second, 0)
+        );
+    }
+
+    #[test]
+    fn it_decodes_special_vifs_ignoring_the_extension_bit() {
+        assert_eq!(ValueInformation::from(0xED).quantity(), Quantity::DateTime);
+        assert_eq!(ValueInformation::from(0x7A).quantity(), Quantity::BusAddress);
+        assert_eq!(ValueInformation::from(0xFD).quantity(), Quantity::Extension(0xFD));
+        assert_eq!(ValueInformation::from(0x6F).quantity(), Quantity::Reserved(0x6F));
+    }
+
+    #[test]
+    fn it_scales_a_value() {
+        let information = ValueInformation::from(0x13);
+        assert_eq!(information.scale(12565.0), 12.565);
+        assert_eq!(ValueInformation::from(0x06).scale(2.0), 2000.0);
+    }
+
+    #[test]
+    fn it_decodes_the_first_extension_table() {
+        assert_eq!(
+            ValueInformation::decode(0xFB, &[0x01]),
+            ValueInformation::new(Quantity::Energy, Unit::WattHour, 6)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFB, &[0x83, 0x22]).quantity(),
+            Quantity::ReactiveEnergy
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFB, &[0x1A]),
+            ValueInformation::new(Quantity::RelativeHumidity, Unit::Percent, -1)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFB, &[0x05]).quantity(),
+            Quantity::ReservedExtension(0xFB, 0x05)
+        );
+    }
+
+    #[test]
+    fn it_decodes_the_main_extension_table() {
+        assert_eq!(
+            ValueInformation::decode(0xFD, &[0x48]),
+            ValueInformation::new(Quantity::Voltage, Unit::Volt, -1)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFD, &[0x59]),
+            ValueInformation::new(Quantity::Current, Unit::Ampere, -3)
+        );
+        assert_eq!(ValueInformation::decode(0xFD, &[0x17]).quantity(), Quantity::ErrorFlags);
+        assert_eq!(
+            ValueInformation::decode(0xFD, &[0x6D]),
+            ValueInformation::new(Quantity::BatteryOperatingTime, Unit::Day, 0)
+        );
+    }
+
+    #[test]
+    fn it_decodes_combinable_extensions() {
+        let information = ValueInformation::decode(0x93, &[0xA2, 0x3C]);
+        assert_eq!(information.quantity(), Quantity::Volume);
+        assert_eq!(
+            information.modifiers(),
+            &[Modifier::PerHour, Modifier::AccumulationAbsoluteNegative]
+        );
+
+        let information = ValueInformation::decode(0xFD, &[0xC8, 0x41]);
+        assert_eq!(information.quantity(), Quantity::Voltage);
+        assert_eq!(information.modifiers(), &[Modifier::LimitExceedCount(Limit::Lower)]);
+
+        let information = ValueInformation::decode(0x82, &[0x4E]);
+        assert_eq!(
+            information.modifiers(),
+            &[Modifier::LimitExceedDateTime(Limit::Upper, Occurrence::Last, Boundary::Begin)]
+        );
+    }
+
+    #[test]
+    fn it_applies_multiplicative_corrections_to_the_exponent() {
+        let information = ValueInformation::decode(0x86, &[0xF3, 0x7D]);
+        assert_eq!(information.exponent(), 3);
+        assert_eq!(
+            information.modifiers(),
+            &[Modifier::MultiplicativeCorrection(-3), Modifier::MultiplicativeCorrection(3)]
+        );
+    }
+
+    #[test]
+    fn it_stops_interpreting_vifes_after_a_manufacturer_specific_vife() {
+        let information = ValueInformation::decode(0x84, &[0xFF, 0x22]);
+        assert_eq!(information.modifiers(), &[Modifier::ManufacturerSpecific]);
+
+        let information = ValueInformation::decode(0xFF, &[0x22]);
+        assert_eq!(information.quantity(), Quantity::ManufacturerSpecific);
+        assert!(information.modifiers().is_empty());
+    }
+
+    #[test]
+    fn it_decodes_every_primary_vif() {
+        for vif in 0x00..=0x7F {
+            let information = ValueInformation::from(vif);
+            assert_eq!(information, ValueInformation::from(vif | 0x80));
+            assert!((-9..=7).contains(&information.exponent()));
+        }
+    }
+}
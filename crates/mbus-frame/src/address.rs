@@ -1,5 +1,9 @@
+use core::fmt;
+use mbus_meta::{Manufacturer, Medium};
+use thiserror::Error;
+
 /// M-Bus Address
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Address {
     /// The address for unconfigured devices (0)
     Unconfigured,
@@ -51,4 +55,399 @@ impl From<Address> for u8 {
             Address::Broadcast => 255,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Length of an encoded secondary address
+pub const SECONDARY_ADDRESS_LENGTH: usize = 8;
+
+/// Wildcard value of the manufacturer field of a secondary address
+const MANUFACTURER_WILDCARD: u16 = 0xFFFF;
+
+/// Wildcard value of the version and medium fields of a secondary address
+const BYTE_WILDCARD: u8 = 0xFF;
+
+/// Wildcard value of a digit of the identification number of a secondary address
+const DIGIT_WILDCARD: u8 = 0xF;
+
+/// M-Bus Secondary Address
+///
+/// A secondary address uniquely identifies a slave on the bus, regardless of
+/// its primary address. It is used to select slaves with the [`Address::Secondary`]
+/// address, and appears in the fixed header of variable data responses.
+///
+/// The format of the secondary address is defined in EN 13757-3 (§6.3) and
+/// EN 13757-7. It is made of the following 8 bytes:
+///
+/// - the identification number, as 8 BCD digits, least significant byte first,
+/// - the manufacturer identifier, least significant byte first,
+/// - the version of the device,
+/// - the medium (device type) of the device.
+///
+/// When selecting slaves, each digit of the identification number may be
+/// replaced by the 0xF wildcard, and the manufacturer, version and medium
+/// fields by 0xFF wildcard bytes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SecondaryAddress {
+    /// Identification number, as BCD digits (e.g. 0x12345678 for 12345678)
+    id: u32,
+
+    /// Manufacturer identifier
+    manufacturer: u16,
+
+    /// Version of the device
+    version: u8,
+
+    /// Medium of the device
+    medium: u8,
+}
+
+impl SecondaryAddress {
+    /// Create a new secondary address
+    ///
+    /// Fails if the identification number has more than 8 decimal digits.
+    pub fn new(
+        id: u32,
+        manufacturer: Manufacturer,
+        version: u8,
+        medium: Medium,
+    ) -> Result<Self, SecondaryAddressError> {
+        if id > 99_999_999 {
+            return Err(SecondaryAddressError::InvalidIdentificationNumber(id));
+        }
+
+        let mut bcd = 0;
+        for digit in 0..8 {
+            bcd |= (id / 10u32.pow(digit) % 10) << (digit * 4);
+        }
+
+        Ok(Self::from_raw(bcd, manufacturer.into(), version, medium.into()))
+    }
+
+    /// Create a new secondary address from raw field values
+    ///
+    /// The identification number is given as BCD digits, where each digit may
+    /// be the 0xF wildcard.
+    pub fn from_raw(id: u32, manufacturer: u16, version: u8, medium: u8) -> Self {
+        Self {
+            id,
+            manufacturer,
+            version,
+            medium,
+        }
+    }
+
+    /// Create a secondary address that matches every slave
+    pub fn wildcard() -> Self {
+        Self::from_raw(u32::MAX, MANUFACTURER_WILDCARD, BYTE_WILDCARD, BYTE_WILDCARD)
+    }
+
+    /// Get the identification number as a decimal number
+    ///
+    /// Returns `None` if any digit is a wildcard or isn't a valid BCD digit.
+    pub fn id(&self) -> Option<u32> {
+        (0..8).rev().try_fold(0, |acc, digit| match self.digit(digit) {
+            value @ 0..=9 => Some(acc * 10 + value as u32),
+            _ => None,
+        })
+    }
+
+    /// Get the identification number as BCD digits
+    pub fn id_bcd(&self) -> u32 {
+        self.id
+    }
+
+    /// Get a digit of the identification number, from 0 (least significant)
+    /// to 7 (most significant)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is greater than 7.
+    pub fn digit(&self, index: usize) -> u8 {
+        assert!(index < 8, "digit index out of range, expected 0 to 7, got {index}");
+        ((self.id >> (index * 4)) & 0xF) as u8
+    }
+
+    /// Replace a digit of the identification number, from 0 (least
+    /// significant) to 7 (most significant)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is greater than 7.
+    pub fn with_digit(&self, index: usize, digit: u8) -> Self {
+        assert!(index < 8, "digit index out of range, expected 0 to 7, got {index}");
+        let shift = index * 4;
+        Self {
+            id: (self.id & !(0xF << shift)) | (((digit & 0xF) as u32) << shift),
+            ..*self
+        }
+    }
+
+    /// Replace the manufacturer
+    ///
+    /// Use [`SecondaryAddress::with_manufacturer_id`] for manufacturers that
+    /// aren't listed in [`Manufacturer`].
+    pub fn with_manufacturer(&self, manufacturer: Manufacturer) -> Self {
+        self.with_manufacturer_id(manufacturer.into())
+    }
+
+    /// Replace the manufacturer with a raw manufacturer identifier
+    pub fn with_manufacturer_id(&self, manufacturer: u16) -> Self {
+        Self { manufacturer, ..*self }
+    }
+
+    /// Replace the version of the device
+    pub fn with_version(&self, version: u8) -> Self {
+        Self { version, ..*self }
+    }
+
+    /// Replace the medium of the device
+    pub fn with_medium(&self, medium: Medium) -> Self {
+        Self {
+            medium: medium.into(),
+            ..*self
+        }
+    }
+
+    /// Get the raw manufacturer identifier
+    pub fn manufacturer_id(&self) -> u16 {
+        self.manufacturer
+    }
+
+    /// Get the manufacturer
+    pub fn manufacturer(&self) -> Manufacturer {
+        self.manufacturer.into()
+    }
+
+    /// Get the version of the device
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Get the medium of the device
+    pub fn medium(&self) -> Medium {
+        self.medium.into()
+    }
+
+    /// Check whether the address contains any wildcard
+    pub fn has_wildcard(&self) -> bool {
+        (0..8).any(|digit| self.digit(digit) == DIGIT_WILDCARD)
+            || self.manufacturer == MANUFACTURER_WILDCARD
+            || self.version == BYTE_WILDCARD
+            || self.medium == BYTE_WILDCARD
+    }
+
+    /// Check whether the given address matches this address, where this
+    /// address may contain wildcards
+    pub fn matches(&self, address: &SecondaryAddress) -> bool {
+        let id_matches = (0..8).all(|digit| {
+            self.digit(digit) == DIGIT_WILDCARD || self.digit(digit) == address.digit(digit)
+        });
+
+        id_matches
+            && (self.manufacturer == MANUFACTURER_WILDCARD || self.manufacturer == address.manufacturer)
+            && (self.version == BYTE_WILDCARD || self.version == address.version)
+            && (self.medium == BYTE_WILDCARD || self.medium == address.medium)
+    }
+
+    /// Convert the secondary address to its 8-byte encoding
+    pub fn to_bytes(&self) -> [u8; SECONDARY_ADDRESS_LENGTH] {
+        let id = self.id.to_le_bytes();
+        let manufacturer = self.manufacturer.to_le_bytes();
+
+        [
+            id[0],
+            id[1],
+            id[2],
+            id[3],
+            manufacturer[0],
+            manufacturer[1],
+            self.version,
+            self.medium,
+        ]
+    }
+
+    /// Decode a secondary address from its 8-byte encoding
+    pub fn from_bytes(bytes: &[u8; SECONDARY_ADDRESS_LENGTH]) -> Self {
+        Self {
+            id: u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            manufacturer: u16::from_le_bytes([bytes[4], bytes[5]]),
+            version: bytes[6],
+            medium: bytes[7],
+        }
+    }
+}
+
+/// Implement conversion from a byte slice to SecondaryAddress
+impl TryFrom<&[u8]> for SecondaryAddress {
+    type Error = SecondaryAddressError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let bytes: &[u8; SECONDARY_ADDRESS_LENGTH] = bytes
+            .try_into()
+            .map_err(|_| SecondaryAddressError::InvalidLength(bytes.len()))?;
+
+        Ok(Self::from_bytes(bytes))
+    }
+}
+
+/// Display the secondary address in the usual `12345678-ABC-01-07` form
+///
+/// Wildcard digits are displayed as `F`, and a wildcard manufacturer as `***`.
+impl fmt::Display for SecondaryAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:08X}-", self.id)?;
+
+        if self.manufacturer == MANUFACTURER_WILDCARD {
+            f.write_str("***")?;
+        } else {
+            for shift in [10, 5, 0] {
+                let letter = ((self.manufacturer >> shift) & 0x1F) as u8 + 64;
+                write!(f, "{}", letter as char)?;
+            }
+        }
+
+        write!(f, "-{:02X}-{:02X}", self.version, self.medium)
+    }
+}
+
+/// Errors that can occur when creating or decoding a secondary address
+#[derive(Error, Debug)]
+pub enum SecondaryAddressError {
+    #[error("invalid length for secondary address, expected 8, got {0}")]
+    InvalidLength(usize),
+    #[error("invalid identification number for secondary address, expected at most 8 digits, got {0}")]
+    InvalidIdentificationNumber(u32),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use alloc::string::ToString;
+
+    const BYTES: [u8; 8] = [0x78, 0x56, 0x34, 0x12, 0x2D, 0x2C, 0x01, 0x07];
+
+    #[test]
+    fn it_decodes_a_secondary_address() {
+        let address = SecondaryAddress::from_bytes(&BYTES);
+        assert_eq!(address.id(), Some(12345678));
+        assert_eq!(address.id_bcd(), 0x12345678);
+        assert_eq!(address.manufacturer(), Manufacturer::KAM);
+        assert_eq!(address.version(), 0x01);
+        assert_eq!(address.medium(), Medium::Water);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_encodes_a_secondary_address() {
+        let address = SecondaryAddress::new(12345678, Manufacturer::KAM, 0x01, Medium::Water).unwrap();
+        assert_eq!(address.to_bytes(), BYTES);
+    }
+
+    #[test]
+    fn it_fails_to_create_a_secondary_address_with_too_many_digits() {
+        let err = SecondaryAddress::new(123456789, Manufacturer::KAM, 0x01, Medium::Water).unwrap_err();
+        assert!(matches!(
+            err,
+            SecondaryAddressError::InvalidIdentificationNumber(123456789)
+        ));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_secondary_address_with_invalid_length() {
+        let err = SecondaryAddress::try_from(&BYTES[..7]).unwrap_err();
+        assert!(matches!(err, SecondaryAddressError::InvalidLength(7)));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_displays_a_secondary_address() {
+        let address = SecondaryAddress::from_bytes(&BYTES);
+        assert_eq!(address.to_string(), "12345678-KAM-01-07");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_displays_a_wildcard_secondary_address() {
+        let address = SecondaryAddress::wildcard().with_digit(7, 1);
+        assert_eq!(address.to_string(), "1FFFFFFF-***-FF-FF");
+    }
+
+    #[test]
+    fn it_returns_no_identification_number_for_wildcard_digits() {
+        let address = SecondaryAddress::from_bytes(&BYTES).with_digit(0, 0xF);
+        assert_eq!(address.id(), None);
+        assert!(address.has_wildcard());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_getting_a_digit_out_of_range() {
+        SecondaryAddress::from_bytes(&BYTES).digit(8);
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_replacing_a_digit_out_of_range() {
+        SecondaryAddress::from_bytes(&BYTES).with_digit(8, 1);
+    }
+
+    #[test]
+    fn it_matches_with_a_full_wildcard() {
+        let address = SecondaryAddress::from_bytes(&BYTES);
+        assert!(SecondaryAddress::wildcard().matches(&address));
+        assert!(!address.has_wildcard());
+    }
+
+    #[test]
+    fn it_matches_with_digit_wildcards() {
+        let address = SecondaryAddress::from_bytes(&BYTES);
+        let pattern = SecondaryAddress::from_raw(0x1234FFFF, 0xFFFF, 0xFF, 0xFF);
+        assert!(pattern.matches(&address));
+
+        let pattern = SecondaryAddress::from_raw(0x1235FFFF, 0xFFFF, 0xFF, 0xFF);
+        assert!(!pattern.matches(&address));
+    }
+
+    #[test]
+    fn it_matches_with_manufacturer_version_and_medium() {
+        let address = SecondaryAddress::from_bytes(&BYTES);
+        let pattern = SecondaryAddress::from_raw(0xFFFFFFFF, 0x2C2D, 0xFF, 0x07);
+        assert!(pattern.matches(&address));
+
+        let pattern = SecondaryAddress::from_raw(0xFFFFFFFF, 0x2C2D, 0x02, 0x07);
+        assert!(!pattern.matches(&address));
+    }
+
+    #[test]
+    fn it_matches_with_replaced_fields() {
+        let address = SecondaryAddress::from_bytes(&BYTES);
+        let pattern = SecondaryAddress::wildcard()
+            .with_manufacturer(address.manufacturer())
+            .with_version(address.version())
+            .with_medium(Medium::Water);
+        assert!(pattern.matches(&address));
+        assert!(!pattern.with_medium(Medium::Gas).matches(&address));
+    }
+
+    #[test]
+    fn it_keeps_the_identifier_of_an_unlisted_manufacturer() {
+        // XYZ isn't a listed manufacturer
+        let address = SecondaryAddress::from_bytes(&BYTES).with_manufacturer_id(0x633A);
+        assert_eq!(address.manufacturer_id(), 0x633A);
+        assert_eq!(address.manufacturer(), Manufacturer::Unknown);
+        assert_eq!(SecondaryAddress::from_bytes(&address.to_bytes()), address);
+
+        let pattern = SecondaryAddress::wildcard().with_manufacturer_id(address.manufacturer_id());
+        assert!(pattern.matches(&address));
+        assert!(!pattern.matches(&SecondaryAddress::from_bytes(&BYTES)));
+    }
+
+    #[test]
+    fn it_does_not_match_a_different_address() {
+        let address = SecondaryAddress::from_bytes(&BYTES);
+        let other = address.with_digit(3, 9);
+        assert!(!other.matches(&address));
+        assert!(address.matches(&address));
+    }
+}
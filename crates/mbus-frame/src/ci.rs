@@ -0,0 +1,238 @@
+/// M-Bus Control Information Field
+///
+/// The control information (CI) field is the first byte of the user data of
+/// a long or control frame. It specifies the application function of the
+/// frame, and the format of the data that follows.
+///
+/// The values of the CI field are defined in EN 13757-3 (§5) and EN 13757-7
+/// (§6). Values that aren't explicitly modelled are kept as is, so that any
+/// CI field round-trips losslessly.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CiField {
+    /// Application reset or select (0x50)
+    ///
+    /// This command flows from the master to the slave and resets the
+    /// application of the slave, optionally with a subcode.
+    ApplicationReset,
+
+    /// Send data to the slave (0x51)
+    ///
+    /// This command flows from the master to the slave and carries data
+    /// records, e.g. to set the time or change the primary address.
+    DataSend,
+
+    /// Select a slave by its secondary address (0x52)
+    ///
+    /// This command flows from the master to the slave and carries the
+    /// secondary address of the slave to select.
+    SelectSlave,
+
+    /// Synchronize action (0x5C)
+    SynchronizeAction,
+
+    /// Application error from the slave (0x70)
+    ApplicationError,
+
+    /// Alarm from the slave (0x71)
+    Alarm,
+
+    /// Variable data structure response with a long header (0x72)
+    VariableDataResponse,
+
+    /// Fixed data structure response (0x73)
+    FixedDataResponse,
+
+    /// Variable data structure response, most significant byte first (0x76)
+    VariableDataResponseMsbFirst,
+
+    /// Fixed data structure response, most significant byte first (0x77)
+    FixedDataResponseMsbFirst,
+
+    /// Variable data structure response without header (0x78)
+    VariableDataResponseNoHeader,
+
+    /// Variable data structure response with a short header (0x7A)
+    VariableDataResponseShortHeader,
+
+    /// Extended link layer I (0x8C)
+    ExtendedLinkLayerI,
+
+    /// Extended link layer II (0x8D)
+    ExtendedLinkLayerII,
+
+    /// Extended link layer III (0x8E)
+    ExtendedLinkLayerIII,
+
+    /// Extended link layer IV (0x8F)
+    ExtendedLinkLayerIV,
+
+    /// Extended link layer V (0x86)
+    ExtendedLinkLayerV,
+
+    /// Authentication and fragmentation sublayer (0x90)
+    AuthenticationFragmentation,
+
+    /// Manufacturer specific application function (0xA0 - 0xB7)
+    ManufacturerSpecific(u8),
+
+    /// Switch the baud rate of the slave (0xB8 - 0xBF)
+    SetBaudRate(BaudRate),
+
+    /// Any other CI field value
+    Other(u8),
+}
+
+/// M-Bus Baud Rate
+///
+/// The baud rates supported by the wired M-Bus, as defined in EN 13757-2
+/// (§5.7) and selectable through the CI field.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BaudRate {
+    Baud300,
+    Baud600,
+    Baud1200,
+    Baud2400,
+    Baud4800,
+    Baud9600,
+    Baud19200,
+    Baud38400,
+}
+
+impl BaudRate {
+    /// Get the number of bits per second of the baud rate
+    pub fn bits_per_second(&self) -> u32 {
+        match self {
+            BaudRate::Baud300 => 300,
+            BaudRate::Baud600 => 600,
+            BaudRate::Baud1200 => 1200,
+            BaudRate::Baud2400 => 2400,
+            BaudRate::Baud4800 => 4800,
+            BaudRate::Baud9600 => 9600,
+            BaudRate::Baud19200 => 19200,
+            BaudRate::Baud38400 => 38400,
+        }
+    }
+}
+
+/// Implement conversion from u8 to CiField
+impl From<u8> for CiField {
+    fn from(value: u8) -> Self {
+        match value {
+            0x50 => CiField::ApplicationReset,
+            0x51 => CiField::DataSend,
+            0x52 => CiField::SelectSlave,
+            0x5C => CiField::SynchronizeAction,
+            0x70 => CiField::ApplicationError,
+            0x71 => CiField::Alarm,
+            0x72 => CiField::VariableDataResponse,
+            0x73 => CiField::FixedDataResponse,
+            0x76 => CiField::VariableDataResponseMsbFirst,
+            0x77 => CiField::FixedDataResponseMsbFirst,
+            0x78 => CiField::VariableDataResponseNoHeader,
+            0x7A => CiField::VariableDataResponseShortHeader,
+            0x8C => CiField::ExtendedLinkLayerI,
+            0x8D => CiField::ExtendedLinkLayerII,
+            0x8E => CiField::ExtendedLinkLayerIII,
+            0x8F => CiField::ExtendedLinkLayerIV,
+            0x86 => CiField::ExtendedLinkLayerV,
+            0x90 => CiField::AuthenticationFragmentation,
+            0xA0..=0xB7 => CiField::ManufacturerSpecific(value),
+            0xB8 => CiField::SetBaudRate(BaudRate::Baud300),
+            0xB9 => CiField::SetBaudRate(BaudRate::Baud600),
+            0xBA => CiField::SetBaudRate(BaudRate::Baud1200),
+            0xBB => CiField::SetBaudRate(BaudRate::Baud2400),
+            0xBC => CiField::SetBaudRate(BaudRate::Baud4800),
+            0xBD => CiField::SetBaudRate(BaudRate::Baud9600),
+            0xBE => CiField::SetBaudRate(BaudRate::Baud19200),
+            0xBF => CiField::SetBaudRate(BaudRate::Baud38400),
+            _ => CiField::Other(value),
+        }
+    }
+}
+
+/// Implement conversion from CiField to u8
+impl From<CiField> for u8 {
+    fn from(ci: CiField) -> Self {
+        match ci {
+            CiField::ApplicationReset => 0x50,
+            CiField::DataSend => 0x51,
+            CiField::SelectSlave => 0x52,
+            CiField::SynchronizeAction => 0x5C,
+            CiField::ApplicationError => 0x70,
+            CiField::Alarm => 0x71,
+            CiField::VariableDataResponse => 0x72,
+            CiField::FixedDataResponse => 0x73,
+            CiField::VariableDataResponseMsbFirst => 0x76,
+            CiField::FixedDataResponseMsbFirst => 0x77,
+            CiField::VariableDataResponseNoHeader => 0x78,
+            CiField::VariableDataResponseShortHeader => 0x7A,
+            CiField::ExtendedLinkLayerI => 0x8C,
+            CiField::ExtendedLinkLayerII => 0x8D,
+            CiField::ExtendedLinkLayerIII => 0x8E,
+            CiField::ExtendedLinkLayerIV => 0x8F,
+            CiField::ExtendedLinkLayerV => 0x86,
+            CiField::AuthenticationFragmentation => 0x90,
+            CiField::ManufacturerSpecific(value) => value,
+            CiField::SetBaudRate(baud_rate) => match baud_rate {
+                BaudRate::Baud300 => 0xB8,
+                BaudRate::Baud600 => 0xB9,
+                BaudRate::Baud1200 => 0xBA,
+                BaudRate::Baud2400 => 0xBB,
+                BaudRate::Baud4800 => 0xBC,
+                BaudRate::Baud9600 => 0xBD,
+                BaudRate::Baud19200 => 0xBE,
+                BaudRate::Baud38400 => 0xBF,
+            },
+            CiField::Other(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_decodes_a_data_send_ci_field() {
+        let ci: CiField = 0x51.into();
+        assert_eq!(ci, CiField::DataSend);
+    }
+
+    #[test]
+    fn it_decodes_a_variable_data_response_ci_field() {
+        let ci: CiField = 0x72.into();
+        assert_eq!(ci, CiField::VariableDataResponse);
+    }
+
+    #[test]
+    fn it_decodes_a_set_baud_rate_ci_field() {
+        let ci: CiField = 0xBD.into();
+        assert_eq!(ci, CiField::SetBaudRate(BaudRate::Baud9600));
+    }
+
+    #[test]
+    fn it_decodes_a_manufacturer_specific_ci_field() {
+        let ci: CiField = 0xA5.into();
+        assert_eq!(ci, CiField::ManufacturerSpecific(0xA5));
+    }
+
+    #[test]
+    fn it_decodes_an_unknown_ci_field() {
+        let ci: CiField = 0x00.into();
+        assert_eq!(ci, CiField::Other(0x00));
+    }
+
+    #[test]
+    fn it_round_trips_every_ci_field_value() {
+        for value in 0..=u8::MAX {
+            let ci: CiField = value.into();
+            assert_eq!(u8::from(ci), value);
+        }
+    }
+
+    #[test]
+    fn it_returns_the_bits_per_second_of_a_baud_rate() {
+        assert_eq!(BaudRate::Baud300.bits_per_second(), 300);
+        assert_eq!(BaudRate::Baud38400.bits_per_second(), 38400);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::frame::{scan, Frame, Scan, MAX_LENGTH};
+use bytes::{Buf, BytesMut};
+use std::io;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Tokio codec for M-Bus frames
+///
+/// The codec turns any async byte stream, such as a serial port or a TCP
+/// connection to a gateway, into a stream of [`Frame`]s when used with
+/// [`tokio_util::codec::Framed`].
+///
+/// Partial frames are kept in the read buffer until complete. Line noise and
+/// frames that fail validation are skipped, and the codec resynchronises on
+/// the next plausible start byte. Only I/O errors are reported.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MBusCodec;
+
+impl MBusCodec {
+    /// Create a new M-Bus codec
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Decoder for MBusCodec {
+    type Item = Frame;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        loop {
+            match scan(src) {
+                Scan::Frame(frame, length) => {
+                    src.advance(length);
+                    return Ok(Some(frame));
+                }
+                Scan::Incomplete => return Ok(None),
+                Scan::Garbage(length) => src.advance(length),
+                Scan::Invalid(_) => src.advance(1),
+            }
+        }
+    }
+}
+
+impl Encoder<Frame> for MBusCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Frame, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.reserve(item.encoded_len());
+        let mut buf = [0u8; MAX_LENGTH];
+        let length = item
+            .encode_into(&mut buf)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        dst.extend_from_slice(&buf[..length]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+    use crate::ci::CiField;
+    use crate::control::Control;
+
+    const SHORT: [u8; 5] = [0x10, 0x40, 0x01, 0x41, 0x16];
+    const LONG: [u8; 12] = [
+        0x68, 0x06, 0x06, 0x68, 0x53, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5A, 0x16,
+    ];
+
+    #[test]
+    fn it_decodes_a_complete_frame() {
+        let mut buf = BytesMut::from(&SHORT[..]);
+        let frame = MBusCodec::new().decode(&mut buf).unwrap().unwrap();
+        assert!(matches!(frame, Frame::Short(_)));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn it_waits_for_the_rest_of_a_partial_long_frame() {
+        let mut codec = MBusCodec::new();
+        let mut buf = BytesMut::from(&LONG[..7]);
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        assert_eq!(buf.len(), 7);
+
+        buf.extend_from_slice(&LONG[7..]);
+        let frame = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(frame.to_bytes(), LONG);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn it_resynchronises_on_bad_bytes() {
+        let mut codec = MBusCodec::new();
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(&[0x00, 0xFF]);
+        buf.extend_from_slice(&[0x10, 0x40, 0x01, 0x42, 0x16]);
+        buf.extend_from_slice(&SHORT);
+        let frame = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(frame.to_bytes(), SHORT);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn it_decodes_consecutive_frames() {
+        let mut codec = MBusCodec::new();
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(&LONG);
+        buf.extend_from_slice(&[0xE5]);
+        assert!(matches!(codec.decode(&mut buf).unwrap(), Some(Frame::Long(_))));
+        assert!(matches!(codec.decode(&mut buf).unwrap(), Some(Frame::Single(_))));
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+    }
+
+    #[test]
+    fn it_encodes_a_frame() {
+        let mut buf = BytesMut::new();
+        let frame = Frame::new_long(Control::Send, Address::Primary(0x01), CiField::from(0x00), &[0x01, 0x02, 0x03]);
+        MBusCodec::new().encode(frame, &mut buf).unwrap();
+        assert_eq!(&buf[..], &LONG);
+    }
+}
@@ -1,7 +1,22 @@
 use thiserror::Error;
 
-/// M-Bus Control Field
-#[derive(Debug, Copy, Clone)]
+/// Position of the frame count bit (FCB) and access demand bit (ACD)
+const FCB_ACD_BIT: u8 = 0x20;
+
+/// Position of the frame count valid bit (FCV) and data flow control bit (DFC)
+const FCV_DFC_BIT: u8 = 0x10;
+
+/// Position of the primary message bit (PRM)
+const PRM_BIT: u8 = 0x40;
+
+/// Mask of the function code
+const FUNCTION_CODE_MASK: u8 = 0x0F;
+
+/// M-Bus Control Function
+///
+/// The function carried by a control field, regardless of its FCB/FCV or
+/// ACD/DFC bits. Use [`ControlField`] to keep track of those bits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Control {
     /// Initialize or Reset the slave device (SND-NKE)
     ///
@@ -60,6 +75,175 @@ impl Control {
     }
 }
 
+/// Direction of a transmission
+///
+/// The direction is given by the primary message bit (PRM) of the control
+/// field.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    /// The frame flows from the master to the slave (PRM = 1)
+    MasterToSlave,
+
+    /// The frame flows from the slave to the master (PRM = 0)
+    SlaveToMaster,
+}
+
+/// M-Bus Control Field
+///
+/// Unlike [`Control`], the control field keeps all the bits of the control
+/// byte, so that a decoded frame is re-encoded to the exact same bytes.
+///
+/// The format of the control field is defined in EN 60870-5-2 (§5.1) and
+/// EN 13757-2 (§5.4). Bits 5 and 4 hold the frame count bit (FCB) and the
+/// frame count valid bit (FCV) in frames sent by the master, and the access
+/// demand bit (ACD) and the data flow control bit (DFC) in frames sent by
+/// the slave.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ControlField {
+    /// Function of the control field
+    control: Control,
+
+    /// Frame count bit (FCB) or access demand bit (ACD)
+    fcb_acd: bool,
+
+    /// Frame count valid bit (FCV) or data flow control bit (DFC)
+    fcv_dfc: bool,
+}
+
+impl ControlField {
+    /// Create a new control field with the default bits of the given function
+    pub fn new(control: Control) -> Self {
+        let value = u8::from(control);
+
+        Self {
+            control,
+            fcb_acd: value & FCB_ACD_BIT != 0,
+            fcv_dfc: value & FCV_DFC_BIT != 0,
+        }
+    }
+
+    /// Get the function of the control field
+    pub fn control(&self) -> Control {
+        self.control
+    }
+
+    /// Get the function code (bits 0 to 3) of the control field
+    pub fn function_code(&self) -> u8 {
+        u8::from(*self) & FUNCTION_CODE_MASK
+    }
+
+    /// Get the direction of the frame carrying this control field
+    pub fn direction(&self) -> Direction {
+        if u8::from(*self) & PRM_BIT != 0 {
+            Direction::MasterToSlave
+        } else {
+            Direction::SlaveToMaster
+        }
+    }
+
+    /// Get the frame count bit (FCB), if the field flows from the master
+    pub fn frame_count_bit(&self) -> Option<bool> {
+        self.is_primary().then_some(self.fcb_acd)
+    }
+
+    /// Get the frame count valid bit (FCV), if the field flows from the master
+    pub fn frame_count_valid(&self) -> Option<bool> {
+        self.is_primary().then_some(self.fcv_dfc)
+    }
+
+    /// Get the access demand bit (ACD), if the field flows from the slave
+    pub fn access_demand(&self) -> Option<bool> {
+        (!self.is_primary()).then_some(self.fcb_acd)
+    }
+
+    /// Get the data flow control bit (DFC), if the field flows from the slave
+    pub fn data_flow_control(&self) -> Option<bool> {
+        (!self.is_primary()).then_some(self.fcv_dfc)
+    }
+
+    /// Set the frame count bit (FCB) of the control field.
+    ///
+    /// Setting the FCB also sets the frame count valid bit (FCV). The
+    /// Initialize control does not use the FCB, and fields flowing from the
+    /// slave carry the ACD instead; both are returned unchanged.
+    pub fn with_frame_count_bit(&self, fcb: bool) -> Self {
+        match self.control {
+            Control::Initialize | Control::Response => *self,
+            _ => Self {
+                fcb_acd: fcb,
+                fcv_dfc: true,
+                ..*self
+            },
+        }
+    }
+
+    /// Set the access demand bit (ACD) of the control field.
+    ///
+    /// Fields flowing from the master carry the FCB instead, and are
+    /// returned unchanged.
+    pub fn with_access_demand(&self, acd: bool) -> Self {
+        if self.is_primary() {
+            return *self;
+        }
+
+        Self {
+            fcb_acd: acd,
+            ..*self
+        }
+    }
+
+    /// Set the data flow control bit (DFC) of the control field.
+    ///
+    /// Fields flowing from the master carry the FCV instead, and are
+    /// returned unchanged.
+    pub fn with_data_flow_control(&self, dfc: bool) -> Self {
+        if self.is_primary() {
+            return *self;
+        }
+
+        Self {
+            fcv_dfc: dfc,
+            ..*self
+        }
+    }
+
+    fn is_primary(&self) -> bool {
+        self.direction() == Direction::MasterToSlave
+    }
+}
+
+impl From<Control> for ControlField {
+    fn from(control: Control) -> Self {
+        Self::new(control)
+    }
+}
+
+/// Implement conversion from u8 to ControlField
+impl TryFrom<u8> for ControlField {
+    type Error = ControlDecodeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Ok(Self {
+            control: value.try_into()?,
+            fcb_acd: value & FCB_ACD_BIT != 0,
+            fcv_dfc: value & FCV_DFC_BIT != 0,
+        })
+    }
+}
+
+impl From<ControlField> for u8 {
+    fn from(field: ControlField) -> Self {
+        let mut value = u8::from(field.control) & !(FCB_ACD_BIT | FCV_DFC_BIT);
+        if field.fcb_acd {
+            value |= FCB_ACD_BIT;
+        }
+        if field.fcv_dfc {
+            value |= FCV_DFC_BIT;
+        }
+        value
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ControlDecodeError {
     #[error("Unsupported communication type.")]
@@ -207,4 +391,62 @@ mod tests {
         let value: u8 = control.into();
         assert_eq!(value, 0x08);
     }
+
+    #[test]
+    fn it_round_trips_every_supported_control_field() {
+        for value in [0x40, 0x53, 0x73, 0x5A, 0x7A, 0x5B, 0x7B, 0x08, 0x18, 0x28, 0x38] {
+            let field: ControlField = value.try_into().unwrap();
+            assert_eq!(u8::from(field), value);
+        }
+    }
+
+    #[test]
+    fn it_decodes_the_bits_of_a_master_control_field() {
+        let field: ControlField = 0x7B.try_into().unwrap();
+        assert_eq!(field.control(), Control::Request);
+        assert_eq!(field.direction(), Direction::MasterToSlave);
+        assert_eq!(field.function_code(), 0x0B);
+        assert_eq!(field.frame_count_bit(), Some(true));
+        assert_eq!(field.frame_count_valid(), Some(true));
+        assert_eq!(field.access_demand(), None);
+        assert_eq!(field.data_flow_control(), None);
+    }
+
+    #[test]
+    fn it_decodes_the_bits_of_a_slave_control_field() {
+        let field: ControlField = 0x28.try_into().unwrap();
+        assert_eq!(field.control(), Control::Response);
+        assert_eq!(field.direction(), Direction::SlaveToMaster);
+        assert_eq!(field.function_code(), 0x08);
+        assert_eq!(field.frame_count_bit(), None);
+        assert_eq!(field.access_demand(), Some(true));
+        assert_eq!(field.data_flow_control(), Some(false));
+    }
+
+    #[test]
+    fn it_sets_the_frame_count_bit_of_a_control_field() {
+        let field = ControlField::new(Control::Request);
+        assert_eq!(u8::from(field.with_frame_count_bit(true)), 0x7B);
+        assert_eq!(u8::from(field.with_frame_count_bit(false)), 0x5B);
+    }
+
+    #[test]
+    fn it_leaves_the_frame_count_bit_of_initialize_and_response_unchanged() {
+        let field = ControlField::new(Control::Initialize);
+        assert_eq!(u8::from(field.with_frame_count_bit(true)), 0x40);
+
+        let field = ControlField::new(Control::Response);
+        assert_eq!(u8::from(field.with_frame_count_bit(true)), 0x08);
+    }
+
+    #[test]
+    fn it_sets_the_access_demand_and_data_flow_control_bits() {
+        let field = ControlField::new(Control::Response)
+            .with_access_demand(true)
+            .with_data_flow_control(true);
+        assert_eq!(u8::from(field), 0x38);
+
+        let field = ControlField::new(Control::Request).with_access_demand(true);
+        assert_eq!(u8::from(field), 0x5B);
+    }
 }
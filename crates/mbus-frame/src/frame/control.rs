@@ -0,0 +1,332 @@
+use super::{output_buffer, EncodeError, Encodable, FrameWithControl};
+use crate::address::Address;
+use crate::ci::CiField;
+use crate::control::ControlField;
+use thiserror::Error;
+
+/// M-Bus Control Frame
+///
+/// An M-Bus control frame is a long frame without user data, which only
+/// carries a control information (CI) field. It is used, amongst others, for
+/// application reset and slave selection commands.
+///
+/// The format of a control frame is defined in EN 60870-5-2 (§3.2) as a frame
+/// with variable length in the FT 1.2 format, with a length field of 3.
+#[derive(Debug, Clone)]
+pub struct ControlFrame {
+    /// Start byte (0x68)
+    ///
+    /// The start byte marks the beginning of the control frame.
+    start1: u8,
+
+    /// Length of the frame (3)
+    length1: u8,
+
+    /// Length of the frame, again (3)
+    length2: u8,
+
+    /// Start byte, again (0x68)
+    start2: u8,
+
+    /// Control
+    ///
+    /// The control byte is used to indicate the type of frame.
+    control: ControlField,
+
+    /// Address
+    ///
+    /// The address byte specifies the address of the addressed slave.
+    address: Address,
+
+    /// Control information
+    ///
+    /// The control information byte specifies the application function.
+    ci: CiField,
+
+    /// Checksum
+    ///
+    /// The checksum byte is used for error detection.
+    ///
+    /// The checksum is calculated as the sum of all bytes in the frame,
+    /// excluding the start and end bytes, modulo 256.
+    checksum: u8,
+
+    /// End byte (0x16)
+    ///
+    /// The end byte marks the end of the control frame.
+    end: u8,
+}
+
+/// Start byte of an M-Bus control frame
+const START_BYTE: u8 = 0x68;
+
+/// End byte of an M-Bus control frame
+const END_BYTE: u8 = 0x16;
+
+/// Value of the length fields of an M-Bus control frame
+pub(crate) const LENGTH_FIELD: u8 = 3;
+
+/// Length of an M-Bus control frame
+const LENGTH: usize = 9; // 4 + 3 + 2
+
+const START_1_INDEX: usize = 0;
+const LENGTH_1_INDEX: usize = 1;
+const LENGTH_2_INDEX: usize = 2;
+const START_2_INDEX: usize = 3;
+const CONTROL_INDEX: usize = 4;
+const ADDRESS_INDEX: usize = 5;
+const CI_INDEX: usize = 6;
+const CHECKSUM_INDEX: usize = 7;
+const END_INDEX: usize = 8;
+
+/// Implementation of the M-Bus control frame
+impl ControlFrame {
+    /// Create a new M-Bus control frame
+    pub fn new(control: impl Into<ControlField>, address: Address, ci: CiField) -> Self {
+        let control = control.into();
+
+        Self {
+            start1: START_BYTE,
+            length1: LENGTH_FIELD,
+            length2: LENGTH_FIELD,
+            start2: START_BYTE,
+            control,
+            address,
+            ci,
+            checksum: Self::compute_checksum(control, address, ci),
+            end: END_BYTE,
+        }
+    }
+
+    /// Get the control field of the frame
+    pub fn control(&self) -> ControlField {
+        self.control
+    }
+
+    /// Get the address of the frame
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Get the control information field of the frame
+    pub fn ci(&self) -> CiField {
+        self.ci
+    }
+
+    /// Compute the checksum of a control frame
+    fn compute_checksum(control: ControlField, address: Address, ci: CiField) -> u8 {
+        u8::from(control)
+            .wrapping_add(address.into())
+            .wrapping_add(ci.into())
+    }
+}
+
+impl Encodable for ControlFrame {
+    type Error = ControlFrameDecodeError;
+
+    /// Get the number of bytes of the encoded control frame.
+    fn encoded_len(&self) -> usize {
+        LENGTH
+    }
+
+    /// Encode the control frame into a byte buffer.
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let buf = output_buffer(buf, LENGTH)?;
+
+        buf[START_1_INDEX] = self.start1;
+        buf[LENGTH_1_INDEX] = self.length1;
+        buf[LENGTH_2_INDEX] = self.length2;
+        buf[START_2_INDEX] = self.start2;
+        buf[CONTROL_INDEX] = self.control.into();
+        buf[ADDRESS_INDEX] = self.address.into();
+        buf[CI_INDEX] = self.ci.into();
+        buf[CHECKSUM_INDEX] = self.checksum;
+        buf[END_INDEX] = self.end;
+
+        Ok(LENGTH)
+    }
+
+    /// Try decoding a byte slice into a control frame.
+    fn try_from_bytes(bytes: &[u8]) -> Result<Self, Self::Error> {
+        // Ensure that the size of the frame is correct
+        if bytes.len() != LENGTH {
+            return Err(ControlFrameDecodeError::InvalidSize(bytes.len()));
+        }
+
+        // Ensure that the start byte is correct
+        if bytes[START_1_INDEX] != START_BYTE {
+            return Err(ControlFrameDecodeError::InvalidStartByte(bytes[START_1_INDEX]));
+        }
+
+        // Ensure that the start bytes match
+        if bytes[START_1_INDEX] != bytes[START_2_INDEX] {
+            return Err(ControlFrameDecodeError::StartByteMismatch(
+                bytes[START_1_INDEX],
+                bytes[START_2_INDEX],
+            ));
+        }
+
+        // Ensure that the length fields are correct
+        if bytes[LENGTH_1_INDEX] != LENGTH_FIELD || bytes[LENGTH_2_INDEX] != LENGTH_FIELD {
+            return Err(ControlFrameDecodeError::InvalidLength(
+                bytes[LENGTH_1_INDEX],
+                bytes[LENGTH_2_INDEX],
+            ));
+        }
+
+        // Ensure that the checksum is correct
+        let control: ControlField = bytes[CONTROL_INDEX].try_into()?;
+        let address: Address = bytes[ADDRESS_INDEX].into();
+        let ci: CiField = bytes[CI_INDEX].into();
+        let checksum = Self::compute_checksum(control, address, ci);
+        if checksum != bytes[CHECKSUM_INDEX] {
+            return Err(ControlFrameDecodeError::InvalidChecksum(
+                checksum,
+                bytes[CHECKSUM_INDEX],
+            ));
+        }
+
+        // Ensure that the end byte is correct
+        if bytes[END_INDEX] != END_BYTE {
+            return Err(ControlFrameDecodeError::InvalidEndByte(bytes[END_INDEX]));
+        }
+
+        Ok(Self {
+            start1: bytes[START_1_INDEX],
+            length1: bytes[LENGTH_1_INDEX],
+            length2: bytes[LENGTH_2_INDEX],
+            start2: bytes[START_2_INDEX],
+            control,
+            address,
+            ci,
+            checksum: bytes[CHECKSUM_INDEX],
+            end: bytes[END_INDEX],
+        })
+    }
+}
+
+impl FrameWithControl for ControlFrame {
+    fn with_frame_count_bit(&self, fcb: bool) -> Self {
+        Self::new(self.control.with_frame_count_bit(fcb), self.address, self.ci)
+    }
+}
+
+/// Errors that can occur when decoding an M-Bus control frame
+#[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum ControlFrameDecodeError {
+    #[error("invalid frame size for control frame, expected 9, got {0}")]
+    InvalidSize(usize),
+    #[error("invalid length fields for control frame, expected 3, got {0} and {1}")]
+    InvalidLength(u8, u8),
+    #[error("invalid start byte for control frame, expected 0x68, got {0:#04x}")]
+    InvalidStartByte(u8),
+    #[error("mismatched start bytes for control frame, expected 0x68, got {0:#04x} and {1:#04x}")]
+    StartByteMismatch(u8, u8),
+    #[error("invalid checksum for control frame, expected {0:#04x}, got {1:#04x}")]
+    InvalidChecksum(u8, u8),
+    #[error("invalid end byte for control frame, expected 0x16, got {0:#04x}")]
+    InvalidEndByte(u8),
+    #[error("failed to decode control field: {0}")]
+    ControlDecodeError(#[from] crate::control::ControlDecodeError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::Control;
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_encodes_the_frame_to_a_byte_vector() {
+        let frame = ControlFrame::new(Control::Send, Address::Primary(0x01), CiField::ApplicationReset);
+        let bytes = frame.to_bytes();
+        assert_eq!(
+            bytes,
+            vec![0x68, 0x03, 0x03, 0x68, 0x53, 0x01, 0x50, 0xA4, 0x16]
+        );
+    }
+
+    #[test]
+    fn it_encodes_the_frame_into_a_buffer() {
+        let frame = ControlFrame::new(Control::Send, Address::Primary(0x01), CiField::ApplicationReset);
+        let mut buf = [0u8; 9];
+        assert_eq!(frame.encode_into(&mut buf).unwrap(), 9);
+        assert_eq!(buf, [0x68, 0x03, 0x03, 0x68, 0x53, 0x01, 0x50, 0xA4, 0x16]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_decodes_a_byte_vector_to_a_frame() {
+        let bytes = vec![0x68, 0x03, 0x03, 0x68, 0x73, 0xFD, 0x52, 0xC2, 0x16];
+        let frame = ControlFrame::try_from_bytes(&bytes).unwrap();
+        assert_eq!(frame.control().control(), Control::Send);
+        assert!(matches!(frame.address(), Address::Secondary));
+        assert_eq!(frame.ci(), CiField::SelectSlave);
+        assert_eq!(frame.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_frame_with_invalid_size() {
+        let bytes = [0x68, 0x03, 0x03, 0x68, 0x53, 0x01, 0x50, 0xA4];
+        let err = ControlFrame::try_from_bytes(&bytes).unwrap_err();
+        assert!(matches!(err, ControlFrameDecodeError::InvalidSize(8)));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_frame_with_invalid_start_byte() {
+        let bytes = [0x69, 0x03, 0x03, 0x68, 0x53, 0x01, 0x50, 0xA4, 0x16];
+        let err = ControlFrame::try_from_bytes(&bytes).unwrap_err();
+        assert!(matches!(err, ControlFrameDecodeError::InvalidStartByte(0x69)));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_frame_with_mismatched_start_bytes() {
+        let bytes = [0x68, 0x03, 0x03, 0x69, 0x53, 0x01, 0x50, 0xA4, 0x16];
+        let err = ControlFrame::try_from_bytes(&bytes).unwrap_err();
+        assert!(matches!(
+            err,
+            ControlFrameDecodeError::StartByteMismatch(0x68, 0x69)
+        ));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_frame_with_invalid_length() {
+        let bytes = [0x68, 0x03, 0x04, 0x68, 0x53, 0x01, 0x50, 0xA4, 0x16];
+        let err = ControlFrame::try_from_bytes(&bytes).unwrap_err();
+        assert!(matches!(
+            err,
+            ControlFrameDecodeError::InvalidLength(0x03, 0x04)
+        ));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_frame_with_invalid_checksum() {
+        let bytes = [0x68, 0x03, 0x03, 0x68, 0x53, 0x01, 0x50, 0xA5, 0x16];
+        let err = ControlFrame::try_from_bytes(&bytes).unwrap_err();
+        assert!(matches!(
+            err,
+            ControlFrameDecodeError::InvalidChecksum(0xA4, 0xA5)
+        ));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_frame_with_invalid_end_byte() {
+        let bytes = [0x68, 0x03, 0x03, 0x68, 0x53, 0x01, 0x50, 0xA4, 0x15];
+        let err = ControlFrame::try_from_bytes(&bytes).unwrap_err();
+        assert!(matches!(err, ControlFrameDecodeError::InvalidEndByte(0x15)));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_updates_the_checksum_when_setting_the_frame_count_bit() {
+        let frame = ControlFrame::new(Control::Send, Address::Primary(0x01), CiField::ApplicationReset);
+        let bytes = frame.with_frame_count_bit(true).to_bytes();
+        assert_eq!(
+            bytes,
+            vec![0x68, 0x03, 0x03, 0x68, 0x73, 0x01, 0x50, 0xC4, 0x16]
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use super::long::{LongFrameDecodeError, MAX_LENGTH};
+use super::{Frame, FrameError};
+
+/// Start byte of an M-Bus short frame
+const SHORT_START_BYTE: u8 = 0x10;
+
+/// Start byte of an M-Bus long frame
+const LONG_START_BYTE: u8 = 0x68;
+
+/// Length of an M-Bus short frame
+const SHORT_LENGTH: usize = 5;
+
+/// Length of the header of an M-Bus long frame (start, length, length, start)
+const LONG_HEADER_LENGTH: usize = 4;
+
+/// Number of bytes of a long frame that are not covered by the length field
+///
+/// These are the four header bytes, the checksum and the end byte.
+const LONG_OVERHEAD: usize = 6;
+
+/// Outcome of scanning the start of a byte buffer for a frame
+#[cfg_attr(not(feature = "alloc"), allow(clippy::large_enum_variant))]
+pub(crate) enum Scan {
+    /// A complete, valid frame spanning the given number of bytes
+    Frame(Frame, usize),
+
+    /// The buffer may start with a frame, but more bytes are needed
+    Incomplete,
+
+    /// The given number of leading bytes cannot start a frame
+    Garbage(usize),
+
+    /// The buffer starts with a complete frame that failed validation
+    Invalid(FrameError),
+}
+
+/// Check whether a byte may be the first byte of a frame
+fn is_start_byte(byte: u8) -> bool {
+    matches!(byte, 0x10 | 0x68 | 0xE5 | 0xA2)
+}
+
+/// Scan the start of a byte buffer for a frame
+///
+/// Bytes that can't start a frame are reported as garbage, up to the next
+/// plausible start byte. Once enough bytes are available, the candidate frame
+/// is handed over to [`Frame::try_from_bytes`] for validation.
+pub(crate) fn scan(bytes: &[u8]) -> Scan {
+    let Some(&first) = bytes.first() else {
+        return Scan::Incomplete;
+    };
+
+    let length = match first {
+        0xE5 | 0xA2 => 1,
+        SHORT_START_BYTE => SHORT_LENGTH,
+        LONG_START_BYTE => {
+            if bytes.len() < LONG_HEADER_LENGTH {
+                return Scan::Incomplete;
+            }
+
+            if bytes[3] != LONG_START_BYTE {
+                return Scan::Invalid(LongFrameDecodeError::StartByteMismatch(first, bytes[3]).into());
+            }
+
+            if bytes[1] != bytes[2] {
+                return Scan::Invalid(LongFrameDecodeError::LengthMismatch(bytes[1], bytes[2]).into());
+            }
+
+            // The length field can't exceed 255, so the frame always fits
+            // within the maximum length of a long frame
+            bytes[1] as usize + LONG_OVERHEAD
+        }
+        _ => {
+            let garbage = bytes
+                .iter()
+                .position(|&byte| is_start_byte(byte))
+                .unwrap_or(bytes.len());
+            return Scan::Garbage(garbage);
+        }
+    };
+
+    if bytes.len() < length {
+        return Scan::Incomplete;
+    }
+
+    match Frame::try_from_bytes(&bytes[..length]) {
+        Ok(frame) => Scan::Frame(frame, length),
+        Err(err) => Scan::Invalid(err),
+    }
+}
+
+/// Streaming M-Bus frame decoder
+///
+/// The decoder accepts bytes as they arrive from the line, in chunks of any
+/// size, and emits complete frames as soon as they are available.
+///
+/// Line noise is skipped up to the next plausible start byte (0x10, 0x68,
+/// 0xE5 or 0xA2). When a candidate frame fails validation, the error is
+/// reported and the decoder resynchronises on the following byte.
+///
+/// ```
+/// use mbus_frame::frame::FrameDecoder;
+///
+/// let mut decoder = FrameDecoder::new();
+/// let mut input: &[u8] = &[0xFF, 0x10, 0x40, 0x01, 0x41, 0x16, 0xE5];
+///
+/// let mut frames = Vec::new();
+/// while !input.is_empty() {
+///     let consumed = decoder.push(input);
+///     input = &input[consumed..];
+///
+///     while let Ok(Some(frame)) = decoder.decode() {
+///         frames.push(frame);
+///     }
+/// }
+///
+/// assert_eq!(frames.len(), 2);
+/// assert_eq!(decoder.discarded(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameDecoder {
+    /// Bytes received, but not yet decoded
+    buffer: [u8; MAX_LENGTH],
+
+    /// Number of bytes currently held in the buffer
+    length: usize,
+
+    /// Number of bytes discarded while resynchronising
+    discarded: usize,
+}
+
+impl FrameDecoder {
+    /// Create a new, empty frame decoder
+    pub fn new() -> Self {
+        Self {
+            buffer: [0; MAX_LENGTH],
+            length: 0,
+            discarded: 0,
+        }
+    }
+
+    /// Append received bytes to the decoder.
+    ///
+    /// The decoder buffers at most one maximum-size frame. Returns the number
+    /// of bytes that were consumed, which may be less than the number of bytes
+    /// given. The remaining bytes should be pushed again after decoding.
+    pub fn push(&mut self, bytes: &[u8]) -> usize {
+        let consumed = bytes.len().min(MAX_LENGTH - self.length);
+        self.buffer[self.length..self.length + consumed].copy_from_slice(&bytes[..consumed]);
+        self.length += consumed;
+        consumed
+    }
+
+    /// Try decoding the next frame from the buffered bytes.
+    ///
+    /// Returns `Ok(None)` when more bytes are needed. When a candidate frame
+    /// fails validation, its first byte is discarded and the error is
+    /// returned; decoding may continue with the next call.
+    pub fn decode(&mut self) -> Result<Option<Frame>, FrameError> {
+        loop {
+            match scan(&self.buffer[..self.length]) {
+                Scan::Frame(frame, length) => {
+                    self.consume(length);
+                    return Ok(Some(frame));
+                }
+                Scan::Incomplete => return Ok(None),
+                Scan::Garbage(length) => self.discard(length),
+                Scan::Invalid(err) => {
+                    self.discard(1);
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    /// Number of bytes currently buffered and awaiting decoding
+    pub fn buffered(&self) -> usize {
+        self.length
+    }
+
+    /// Number of bytes discarded while resynchronising
+    pub fn discarded(&self) -> usize {
+        self.discarded
+    }
+
+    /// Drop all buffered bytes and reset the discarded byte count
+    pub fn reset(&mut self) {
+        self.length = 0;
+        self.discarded = 0;
+    }
+
+    /// Remove the given number of bytes from the start of the buffer
+    fn consume(&mut self, length: usize) {
+        self.buffer.copy_within(length..self.length, 0);
+        self.length -= length;
+    }
+
+    /// Remove the given number of bytes from the start of the buffer, and
+    /// account for them as discarded
+    fn discard(&mut self, length: usize) {
+        self.consume(length);
+        self.discarded += length;
+    }
+}
+
+impl Default for FrameDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+    use crate::ci::CiField;
+    use crate::control::Control;
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+
+    const SHORT: [u8; 5] = [0x10, 0x40, 0x01, 0x41, 0x16];
+    const LONG: [u8; 12] = [
+        0x68, 0x06, 0x06, 0x68, 0x53, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5A, 0x16,
+    ];
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_decodes_a_complete_frame() {
+        let mut decoder = FrameDecoder::new();
+        assert_eq!(decoder.push(&SHORT), 5);
+        let frame = decoder.decode().unwrap().unwrap();
+        assert_eq!(frame.to_bytes(), SHORT);
+        assert_eq!(decoder.buffered(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_decodes_a_frame_received_byte_by_byte() {
+        let mut decoder = FrameDecoder::new();
+        for &byte in &LONG[..LONG.len() - 1] {
+            decoder.push(&[byte]);
+            assert!(decoder.decode().unwrap().is_none());
+        }
+        decoder.push(&LONG[LONG.len() - 1..]);
+        let frame = decoder.decode().unwrap().unwrap();
+        assert_eq!(frame.to_bytes(), LONG);
+    }
+
+    #[test]
+    fn it_re_encodes_a_decoded_frame_into_a_buffer() {
+        let mut decoder = FrameDecoder::new();
+        decoder.push(&LONG);
+        let frame = decoder.decode().unwrap().unwrap();
+        let mut buf = [0u8; MAX_LENGTH];
+        let length = frame.encode_into(&mut buf).unwrap();
+        assert_eq!(buf[..length], LONG);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_decodes_consecutive_frames_from_a_single_chunk() {
+        let mut decoder = FrameDecoder::new();
+        let mut bytes = vec![0xE5];
+        bytes.extend_from_slice(&SHORT);
+        bytes.extend_from_slice(&LONG);
+        decoder.push(&bytes);
+        assert!(matches!(decoder.decode().unwrap(), Some(Frame::Single(_))));
+        assert!(matches!(decoder.decode().unwrap(), Some(Frame::Short(_))));
+        assert!(matches!(decoder.decode().unwrap(), Some(Frame::Long(_))));
+        assert!(decoder.decode().unwrap().is_none());
+    }
+
+    #[test]
+    fn it_skips_garbage_before_a_frame() {
+        let mut decoder = FrameDecoder::new();
+        decoder.push(&[0x00, 0xFF, 0x42]);
+        decoder.push(&SHORT);
+        assert!(matches!(decoder.decode().unwrap(), Some(Frame::Short(_))));
+        assert_eq!(decoder.discarded(), 3);
+    }
+
+    #[test]
+    fn it_resynchronises_after_an_invalid_frame() {
+        let mut decoder = FrameDecoder::new();
+        decoder.push(&[0x10, 0x40, 0x01, 0x42, 0x16]);
+        decoder.push(&SHORT);
+        assert!(matches!(decoder.decode(), Err(FrameError::ShortFrame(_))));
+        assert!(matches!(decoder.decode().unwrap(), Some(Frame::Short(_))));
+        assert_eq!(decoder.discarded(), 5);
+    }
+
+    #[test]
+    fn it_rejects_a_long_frame_header_with_mismatched_lengths() {
+        let mut decoder = FrameDecoder::new();
+        decoder.push(&[0x68, 0x06, 0x07, 0x68]);
+        assert!(matches!(
+            decoder.decode(),
+            Err(FrameError::LongFrame(LongFrameDecodeError::LengthMismatch(0x06, 0x07)))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_decodes_a_long_frame_of_maximum_length() {
+        let data = vec![0xAA; MAX_LENGTH - 9];
+        let bytes = Frame::new_long(Control::Send, Address::Primary(1), CiField::DataSend, &data)
+            .to_bytes();
+        let mut decoder = FrameDecoder::new();
+        assert_eq!(decoder.push(&bytes), MAX_LENGTH);
+        assert!(matches!(decoder.decode().unwrap(), Some(Frame::Long(_))));
+    }
+
+    #[test]
+    fn it_decodes_a_long_frame_of_maximum_length_encoded_into_a_buffer() {
+        let frame = Frame::new_long(Control::Send, Address::Primary(1), CiField::DataSend, &[0xAA; MAX_LENGTH - 9]);
+        let mut buf = [0u8; MAX_LENGTH];
+        assert_eq!(frame.encode_into(&mut buf).unwrap(), MAX_LENGTH);
+        let mut decoder = FrameDecoder::new();
+        assert_eq!(decoder.push(&buf), MAX_LENGTH);
+        assert!(matches!(decoder.decode().unwrap(), Some(Frame::Long(_))));
+    }
+
+    #[test]
+    fn it_only_consumes_bytes_that_fit_in_the_buffer() {
+        let mut decoder = FrameDecoder::new();
+        let bytes = [0xE5; MAX_LENGTH + 10];
+        assert_eq!(decoder.push(&bytes), MAX_LENGTH);
+        assert!(decoder.decode().unwrap().is_some());
+        assert_eq!(decoder.push(&bytes[MAX_LENGTH..]), 1);
+    }
+
+    #[test]
+    fn it_resets_the_decoder() {
+        let mut decoder = FrameDecoder::new();
+        decoder.push(&[0x00, 0x10, 0x40]);
+        assert!(decoder.decode().unwrap().is_none());
+        decoder.reset();
+        assert_eq!(decoder.buffered(), 0);
+        assert_eq!(decoder.discarded(), 0);
+    }
+}
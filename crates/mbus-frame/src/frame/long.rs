@@ -1,6 +1,7 @@
-use super::{Encodable, FrameWithControl};
+use super::{output_buffer, payload_from_slice, EncodeError, Encodable, FrameWithControl, Payload};
 use crate::address::Address;
-use crate::control::Control;
+use crate::ci::CiField;
+use crate::control::ControlField;
 use thiserror::Error;
 
 /// M-Bus Long Frame
@@ -29,15 +30,21 @@ pub struct LongFrame {
     /// Control
     ///
     /// The control byte is used to indicate the type of frame.
-    control: Control,
+    control: ControlField,
 
     /// Address
     ///
     /// The address byte specifies the address of the addressed slave.
     address: Address,
 
-    /// User data
-    data: Vec<u8>,
+    /// Control information
+    ///
+    /// The control information byte specifies the application function, and
+    /// the format of the user data.
+    ci: CiField,
+
+    /// User data, following the control information byte
+    data: Payload,
 
     /// Checksum
     ///
@@ -61,15 +68,19 @@ const END_BYTE: u8 = 0x16;
 
 /// Minimum length of an M-Bus long frame
 ///
-/// The minimum length of an M-Bus long frame is 8 bytes, which
-/// corresponds to a frame with no user data.
-const MIN_LENGTH: usize = 8; // 6 + 0 + 2
+/// The minimum length of an M-Bus long frame is 10 bytes, which
+/// corresponds to a frame with a CI field and a single byte of further user
+/// data. A frame without further user data is a [`ControlFrame`].
+///
+/// [`ControlFrame`]: super::ControlFrame
+const MIN_LENGTH: usize = 10; // 7 + 1 + 2
 
 /// Maximum length of an M-Bus long frame
 ///
-/// The maximum length of an M-Bus long frame is 259 bytes, which
-/// corresponds to a frame with 253 bytes of user data
-const MAX_LENGTH: usize = 259; // 6 + 253 + 2
+/// The maximum length of an M-Bus long frame is 261 bytes, which
+/// corresponds to a length field of 255, and thus to a frame with a CI field
+/// and 252 bytes of further user data.
+pub(crate) const MAX_LENGTH: usize = 261; // 7 + 252 + 2
 
 const START_1_INDEX: usize = 0;
 const LENGTH_1_INDEX: usize = 1;
@@ -77,30 +88,89 @@ const LENGTH_2_INDEX: usize = 2;
 const START_2_INDEX: usize = 3;
 const CONTROL_INDEX: usize = 4;
 const ADDRESS_INDEX: usize = 5;
-const DATA_START_INDEX: usize = 6;
+/// Maximum length of the user data of an M-Bus long frame, following the
+/// control information byte
+pub const MAX_DATA_LENGTH: usize = 252;
+
+const CI_INDEX: usize = 6;
+const DATA_START_INDEX: usize = 7;
 
 /// Implementation of the M-Bus long frame
 impl LongFrame {
-    pub fn new(control: Control, address: Address, data: &[u8]) -> Self {
-        let length = 2 + data.len() as u8;
+    /// Create a new M-Bus long frame
+    ///
+    /// # Panics
+    ///
+    /// Panics if the user data is empty or longer than [`MAX_DATA_LENGTH`]
+    /// bytes. Use [`LongFrame::try_new`] to handle invalid user data
+    /// gracefully.
+    pub fn new(control: impl Into<ControlField>, address: Address, ci: CiField, data: &[u8]) -> Self {
+        Self::try_new(control, address, ci, data)
+            .expect("user data is empty or exceeds the maximum length of a long frame")
+    }
+
+    /// Try creating a new M-Bus long frame
+    ///
+    /// Fails if the user data is longer than [`MAX_DATA_LENGTH`] bytes, as
+    /// the length field of the frame can't exceed 255. Fails as well if the
+    /// user data is empty, as such a frame is a [`ControlFrame`].
+    ///
+    /// [`ControlFrame`]: super::ControlFrame
+    pub fn try_new(
+        control: impl Into<ControlField>,
+        address: Address,
+        ci: CiField,
+        data: &[u8],
+    ) -> Result<Self, LongFrameEncodeError> {
+        if data.is_empty() {
+            return Err(LongFrameEncodeError::EmptyData);
+        }
+        if data.len() > MAX_DATA_LENGTH {
+            return Err(LongFrameEncodeError::DataTooLong(data.len()));
+        }
+
+        let control = control.into();
+        let length = (3 + data.len()) as u8;
 
-        Self {
+        Ok(Self {
             start1: START_BYTE,
             length1: length,
             length2: length,
             start2: START_BYTE,
-            control: control.clone(),
-            address: address.clone(),
-            data: data.to_vec(),
-            checksum: Self::compute_checksum(control, address, data),
+            control,
+            address,
+            ci,
+            data: payload_from_slice(data),
+            checksum: Self::compute_checksum(control, address, ci, data),
             end: END_BYTE,
-        }
+        })
+    }
+
+    /// Get the control field of the frame
+    pub fn control(&self) -> ControlField {
+        self.control
+    }
+
+    /// Get the address of the frame
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Get the control information field of the frame
+    pub fn ci(&self) -> CiField {
+        self.ci
+    }
+
+    /// Get the user data of the frame, following the control information field
+    pub fn data(&self) -> &[u8] {
+        &self.data
     }
 
     /// Compute the checksum of a long frame
-    fn compute_checksum(control: Control, address: Address, data: &[u8]) -> u8 {
+    fn compute_checksum(control: ControlField, address: Address, ci: CiField, data: &[u8]) -> u8 {
         u8::from(control)
             .wrapping_add(address.into())
+            .wrapping_add(ci.into())
             .wrapping_add(data.iter().fold(0u8, |acc, &b| acc.wrapping_add(b)))
     }
 }
@@ -108,35 +178,66 @@ impl LongFrame {
 impl Encodable for LongFrame {
     type Error = LongFrameDecodeError;
 
-    /// Convert the long frame to a byte vector.
-    fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-
-        bytes.push(self.start1);
-        bytes.push(self.length1);
-        bytes.push(self.length2);
-        bytes.push(self.start2);
-        bytes.push(self.control.into());
-        bytes.push(self.address.into());
-        bytes.extend_from_slice(&self.data);
-        bytes.push(self.checksum);
-        bytes.push(self.end);
+    /// Get the number of bytes of the encoded long frame.
+    fn encoded_len(&self) -> usize {
+        DATA_START_INDEX + self.data.len() + 2
+    }
 
-        bytes
+    /// Encode the long frame into a byte buffer.
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let length = self.encoded_len();
+        let buf = output_buffer(buf, length)?;
+
+        buf[START_1_INDEX] = self.start1;
+        buf[LENGTH_1_INDEX] = self.length1;
+        buf[LENGTH_2_INDEX] = self.length2;
+        buf[START_2_INDEX] = self.start2;
+        buf[CONTROL_INDEX] = self.control.into();
+        buf[ADDRESS_INDEX] = self.address.into();
+        buf[CI_INDEX] = self.ci.into();
+        buf[DATA_START_INDEX..length - 2].copy_from_slice(&self.data);
+        buf[length - 2] = self.checksum;
+        buf[length - 1] = self.end;
+
+        Ok(length)
     }
 
-    /// Try decoding a byte slice into a short frame.
+    /// Try decoding a byte slice into a long frame.
     fn try_from_bytes(bytes: &[u8]) -> Result<Self, Self::Error> {
+        LongFrameRef::try_from_bytes(bytes).map(|frame| frame.to_owned())
+    }
+}
+
+/// Borrowed M-Bus Long Frame
+///
+/// A zero-copy view of a long frame, which is validated the same way as a
+/// [`LongFrame`] but borrows its user data from the input buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct LongFrameRef<'a> {
+    /// Control
+    control: ControlField,
+
+    /// Address
+    address: Address,
+
+    /// Control information
+    ci: CiField,
+
+    /// User data, following the control information byte
+    data: &'a [u8],
+
+    /// Checksum
+    checksum: u8,
+}
+
+impl<'a> LongFrameRef<'a> {
+    /// Try decoding a byte slice into a borrowed long frame.
+    pub fn try_from_bytes(bytes: &'a [u8]) -> Result<Self, LongFrameDecodeError> {
         // Ensure that the size of the frame isn't too short, or too long
         if bytes.len() < MIN_LENGTH || bytes.len() > MAX_LENGTH {
             return Err(LongFrameDecodeError::InvalidSize(bytes.len()));
         }
 
-        // Ensure that the size of the frame isn't too long
-        if bytes.len() > 253 {
-            return Err(LongFrameDecodeError::InvalidSize(bytes.len()));
-        }
-
         // Ensure that the start byte is correct
         if bytes[START_1_INDEX] != START_BYTE {
             return Err(LongFrameDecodeError::InvalidStartByte(bytes[START_1_INDEX]));
@@ -160,8 +261,8 @@ impl Encodable for LongFrame {
 
         // Ensure that the length field is correct
         let declared_length = bytes[LENGTH_1_INDEX];
-        let expected_frame_size = 4 + declared_length + 2;
-        if bytes.len() != expected_frame_size as usize {
+        let expected_frame_size = 4 + declared_length as usize + 2;
+        if bytes.len() != expected_frame_size {
             return Err(LongFrameDecodeError::InvalidLength(
                 declared_length,
                 (bytes.len() - 6) as u8,
@@ -169,10 +270,12 @@ impl Encodable for LongFrame {
         }
 
         // Ensure that the checksum is correct
-        let checksum = Self::compute_checksum(
+        let data_end_index = bytes.len() - 2;
+        let checksum = LongFrame::compute_checksum(
             bytes[CONTROL_INDEX].try_into()?,
             bytes[ADDRESS_INDEX].into(),
-            &bytes[DATA_START_INDEX..DATA_START_INDEX + (bytes[LENGTH_1_INDEX] as usize - 2)],
+            bytes[CI_INDEX].into(),
+            &bytes[DATA_START_INDEX..data_end_index],
         );
 
         let checksum_byte_index = bytes.len() - 2;
@@ -190,33 +293,73 @@ impl Encodable for LongFrame {
         }
 
         Ok(Self {
-            start1: bytes[START_1_INDEX],
-            length1: bytes[LENGTH_1_INDEX],
-            length2: bytes[LENGTH_2_INDEX],
-            start2: bytes[START_2_INDEX],
             control: bytes[CONTROL_INDEX].try_into()?,
             address: bytes[ADDRESS_INDEX].into(),
-            data: bytes[DATA_START_INDEX..DATA_START_INDEX + (bytes[LENGTH_1_INDEX] as usize - 2)]
-                .to_vec(),
+            ci: bytes[CI_INDEX].into(),
+            data: &bytes[DATA_START_INDEX..data_end_index],
             checksum: bytes[checksum_byte_index],
-            end: bytes[stop_byte_index],
         })
     }
+
+    /// Get the control field of the frame
+    pub fn control(&self) -> ControlField {
+        self.control
+    }
+
+    /// Get the address of the frame
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Get the control information field of the frame
+    pub fn ci(&self) -> CiField {
+        self.ci
+    }
+
+    /// Get the user data of the frame, following the control information field
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Copy the borrowed frame into an owned [`LongFrame`]
+    pub fn to_owned(&self) -> LongFrame {
+        let length = 3 + self.data.len() as u8;
+
+        LongFrame {
+            start1: START_BYTE,
+            length1: length,
+            length2: length,
+            start2: START_BYTE,
+            control: self.control,
+            address: self.address,
+            ci: self.ci,
+            data: payload_from_slice(self.data),
+            checksum: self.checksum,
+            end: END_BYTE,
+        }
+    }
 }
 
 impl FrameWithControl for LongFrame {
     fn with_frame_count_bit(&self, fcb: bool) -> Self {
-        let mut new = self.clone();
-        new.control = self.control.with_frame_count_bit(fcb);
-        new
+        Self::new(self.control.with_frame_count_bit(fcb), self.address, self.ci, &self.data)
     }
 }
 
+/// Errors that can occur when creating an M-Bus long frame
+#[derive(Error, Debug)]
+pub enum LongFrameEncodeError {
+    #[error("user data too long for long frame, expected <=252 bytes, got {0}")]
+    DataTooLong(usize),
+    #[error("missing user data for long frame, use a control frame instead")]
+    EmptyData,
+}
+
 /// Errors that can occur when decoding an M-Bus long frame
 #[derive(Error, Debug)]
 #[allow(clippy::enum_variant_names)]
 pub enum LongFrameDecodeError {
-    #[error("invalid frame size for long frame, expected >=8, got {0}")]
+    #[error("invalid frame size for long frame, expected 10 to 261, got {0}")]
     InvalidSize(usize),
     #[error("invalid length for long frame, expected {0}, got {1}")]
     InvalidLength(u8, u8),
@@ -237,13 +380,22 @@ pub enum LongFrameDecodeError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::control::Control;
+    #[cfg(feature = "alloc")]
+    use crate::frame::{ControlFrame, Frame};
+    #[cfg(feature = "alloc")]
+    use proptest::prelude::*;
+    #[cfg(feature = "alloc")]
+    use alloc::{vec, vec::Vec};
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn it_encodes_the_frame_to_a_byte_vector() {
         let frame = LongFrame::new(
             Control::Send,
             Address::Primary(0x01),
-            &[0x00, 0x01, 0x02, 0x03],
+            CiField::from(0x00),
+            &[0x01, 0x02, 0x03],
         );
         let bytes = frame.to_bytes();
         assert_eq!(
@@ -254,9 +406,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_encodes_a_frame_of_maximum_length_into_a_buffer() {
+        let frame = LongFrame::new(Control::Send, Address::Primary(0x01), CiField::DataSend, &[0xAA; MAX_DATA_LENGTH]);
+        let mut buf = [0u8; MAX_LENGTH];
+        assert_eq!(frame.encode_into(&mut buf).unwrap(), MAX_LENGTH);
+        let decoded = LongFrame::try_from_bytes(&buf).unwrap();
+        assert_eq!(decoded.data(), &[0xAA; MAX_DATA_LENGTH]);
+    }
+
+    #[test]
+    fn it_encodes_the_frame_into_a_buffer() {
+        let frame = LongFrame::new(
+            Control::Send,
+            Address::Primary(0x01),
+            CiField::from(0x00),
+            &[0x01, 0x02, 0x03],
+        );
+        let mut buf = [0u8; 16];
+        let length = frame.encode_into(&mut buf).unwrap();
+        assert_eq!(
+            &buf[..length],
+            &[0x68, 0x06, 0x06, 0x68, 0x53, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5A, 0x16]
+        );
+    }
+
+    #[test]
+    fn it_fails_to_encode_the_frame_into_a_buffer_too_small() {
+        let frame = LongFrame::new(Control::Send, Address::Primary(0x01), CiField::DataSend, &[0x01]);
+        let mut buf = [0u8; 4];
+        let err = frame.encode_into(&mut buf).unwrap_err();
+        assert!(matches!(err, EncodeError::BufferTooSmall(10, 4)));
+    }
+
     #[test]
     fn it_decodes_a_byte_vector_to_a_frame() {
-        let bytes = vec![
+        let bytes = [
             0x68, 0x06, 0x06, 0x68, 0x53, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5A, 0x16,
         ];
         let frame = LongFrame::try_from_bytes(&bytes).unwrap();
@@ -264,21 +449,23 @@ mod tests {
         assert_eq!(frame.length1, 0x06);
         assert_eq!(frame.length2, 0x06);
         assert_eq!(frame.start2, 0x68);
-        matches!(frame.control, Control::Send);
+        matches!(frame.control.control(), Control::Send);
         matches!(frame.address, Address::Primary(0x01));
-        assert_eq!(frame.data, vec![0x00, 0x01, 0x02, 0x03]);
+        assert_eq!(frame.ci, CiField::Other(0x00));
+        assert_eq!(frame.data, [0x01, 0x02, 0x03]);
         assert_eq!(frame.checksum, 0x5A);
         assert_eq!(frame.end, 0x16);
     }
 
     #[test]
-    fn it_fails_to_decode_a_frame_shorter_than_8_bytes() {
-        let bytes = vec![0x68, 0x06, 0x06, 0x68, 0x53, 0x01, 0x00];
+    fn it_fails_to_decode_a_frame_shorter_than_10_bytes() {
+        let bytes = [0x68, 0x06, 0x06, 0x68, 0x53, 0x01, 0x00];
         let err = LongFrame::try_from_bytes(&bytes).unwrap_err();
         assert!(matches!(err, LongFrameDecodeError::InvalidSize(7)));
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn it_fails_to_decode_a_frame_longer_than_261_bytes() {
         let mut bytes = vec![0x68, 0xFF, 0xFF, 0x68, 0x53, 0x01];
         bytes.extend(vec![0x00; 255]);
@@ -289,8 +476,60 @@ mod tests {
     }
 
     #[test]
-    fn it_fails_to_decode_a_frame_with_invalid_start_byte() {
+    fn it_decodes_the_ci_field_of_a_frame() {
+        let bytes = [0x68, 0x04, 0x04, 0x68, 0x08, 0x01, 0x72, 0xAA, 0x25, 0x16];
+        let frame = LongFrame::try_from_bytes(&bytes).unwrap();
+        assert_eq!(frame.ci(), CiField::VariableDataResponse);
+        assert_eq!(frame.data(), &[0xAA]);
+    }
+
+    #[test]
+    fn it_decodes_a_borrowed_frame_without_copying_the_data() {
+        let bytes = [0x68, 0x04, 0x04, 0x68, 0x08, 0x01, 0x72, 0xAA, 0x25, 0x16];
+        let frame = LongFrameRef::try_from_bytes(&bytes).unwrap();
+        assert_eq!(frame.control().control(), Control::Response);
+        assert_eq!(frame.ci(), CiField::VariableDataResponse);
+        assert_eq!(frame.data().as_ptr(), bytes[7..].as_ptr());
+        assert_eq!(frame.data(), &[0xAA]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_converts_a_borrowed_frame_to_an_owned_frame() {
         let bytes = vec![
+            0x68, 0x06, 0x06, 0x68, 0x73, 0x01, 0x00, 0x01, 0x02, 0x03, 0x7A, 0x16,
+        ];
+        let frame = LongFrameRef::try_from_bytes(&bytes).unwrap().to_owned();
+        assert_eq!(frame.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_borrowed_frame_with_invalid_checksum() {
+        let bytes = [0x68, 0x04, 0x04, 0x68, 0x08, 0x01, 0x72, 0xAA, 0x26, 0x16];
+        let err = LongFrameRef::try_from_bytes(&bytes).unwrap_err();
+        assert!(matches!(
+            err,
+            LongFrameDecodeError::InvalidChecksum(0x25, 0x26)
+        ));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_frame_without_ci_field() {
+        let bytes = [0x68, 0x02, 0x02, 0x68, 0x53, 0x01, 0x54, 0x16];
+        let err = LongFrame::try_from_bytes(&bytes).unwrap_err();
+        assert!(matches!(err, LongFrameDecodeError::InvalidSize(8)));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_frame_without_user_data() {
+        let bytes = [0x68, 0x03, 0x03, 0x68, 0x53, 0x01, 0x51, 0xA5, 0x16];
+        let err = LongFrame::try_from_bytes(&bytes).unwrap_err();
+        assert!(matches!(err, LongFrameDecodeError::InvalidSize(9)));
+    }
+
+    #[test]
+    fn it_fails_to_decode_a_frame_with_invalid_start_byte() {
+        let bytes = [
             0x69, 0x06, 0x06, 0x68, 0x53, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5A, 0x16,
         ];
         let err = LongFrame::try_from_bytes(&bytes).unwrap_err();
@@ -299,7 +538,7 @@ mod tests {
 
     #[test]
     fn it_fails_to_decode_a_frame_with_mismatched_start_bytes() {
-        let bytes = vec![
+        let bytes = [
             0x68, 0x06, 0x06, 0x69, 0x53, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5A, 0x16,
         ];
         let err = LongFrame::try_from_bytes(&bytes).unwrap_err();
@@ -311,7 +550,7 @@ mod tests {
 
     #[test]
     fn it_fails_to_decode_a_frame_with_mismatched_length_fields() {
-        let bytes = vec![
+        let bytes = [
             0x68, 0x06, 0x07, 0x68, 0x53, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5A, 0x16,
         ];
         let err = LongFrame::try_from_bytes(&bytes).unwrap_err();
@@ -323,7 +562,7 @@ mod tests {
 
     #[test]
     fn it_fails_to_decode_a_frame_with_invalid_length() {
-        let bytes = vec![
+        let bytes = [
             0x68, 0x07, 0x07, 0x68, 0x53, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5A, 0x16,
         ];
         let err = LongFrame::try_from_bytes(&bytes).unwrap_err();
@@ -335,7 +574,7 @@ mod tests {
 
     #[test]
     fn it_fails_to_decode_a_frame_with_invalid_checksum() {
-        let bytes = vec![
+        let bytes = [
             0x68, 0x06, 0x06, 0x68, 0x53, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5B, 0x16,
         ];
         let err = LongFrame::try_from_bytes(&bytes).unwrap_err();
@@ -347,7 +586,7 @@ mod tests {
 
     #[test]
     fn it_fails_to_decode_a_frame_with_invalid_end_byte() {
-        let bytes = vec![
+        let bytes = [
             0x68, 0x06, 0x06, 0x68, 0x53, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5A, 0x15,
         ];
         let err = LongFrame::try_from_bytes(&bytes).unwrap_err();
@@ -356,10 +595,128 @@ mod tests {
 
     #[test]
     fn it_fails_to_decode_a_frame_with_invalid_control_byte() {
-        let bytes = vec![
+        let bytes = [
             0x68, 0x06, 0x06, 0x68, 0x54, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5A, 0x16,
         ];
         let err = LongFrame::try_from_bytes(&bytes).unwrap_err();
         assert!(matches!(err, LongFrameDecodeError::ControlDecodeError(_)));
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_re_encodes_a_decoded_frame_to_the_same_bytes() {
+        let bytes = vec![
+            0x68, 0x06, 0x06, 0x68, 0x73, 0x01, 0x00, 0x01, 0x02, 0x03, 0x7A, 0x16,
+        ];
+        let frame = LongFrame::try_from_bytes(&bytes).unwrap();
+        assert_eq!(frame.to_bytes(), bytes);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_updates_the_checksum_when_setting_the_frame_count_bit() {
+        let frame = LongFrame::new(
+            Control::Send,
+            Address::Primary(0x01),
+            CiField::from(0x00),
+            &[0x01, 0x02, 0x03],
+        );
+        let bytes = frame.with_frame_count_bit(true).to_bytes();
+        assert_eq!(
+            bytes,
+            vec![0x68, 0x06, 0x06, 0x68, 0x73, 0x01, 0x00, 0x01, 0x02, 0x03, 0x7A, 0x16]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_creates_a_frame_with_the_maximum_data_length() {
+        let data = vec![0xAA; MAX_DATA_LENGTH];
+        let frame = LongFrame::try_new(Control::Send, Address::Primary(0x01), CiField::DataSend, &data)
+            .unwrap();
+        let bytes = frame.to_bytes();
+        assert_eq!(bytes.len(), MAX_LENGTH);
+        assert_eq!(bytes[LENGTH_1_INDEX], 0xFF);
+        assert_eq!(bytes[LENGTH_2_INDEX], 0xFF);
+    }
+
+    #[test]
+    fn it_fails_to_create_a_frame_with_oversize_data() {
+        let data = [0xAA; MAX_DATA_LENGTH + 1];
+        let err = LongFrame::try_new(Control::Send, Address::Primary(0x01), CiField::DataSend, &data)
+            .unwrap_err();
+        assert!(matches!(err, LongFrameEncodeError::DataTooLong(253)));
+    }
+
+    #[test]
+    fn it_fails_to_create_a_frame_without_data() {
+        let err = LongFrame::try_new(Control::Send, Address::Primary(1), CiField::DataSend, &[]).unwrap_err();
+        assert!(matches!(err, LongFrameEncodeError::EmptyData));
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_creating_a_frame_with_oversize_data() {
+        let data = [0xAA; MAX_DATA_LENGTH + 1];
+        LongFrame::new(Control::Send, Address::Primary(0x01), CiField::DataSend, &data);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_decodes_a_frame_with_the_maximum_length() {
+        let data = vec![0xAA; MAX_DATA_LENGTH];
+        let bytes = LongFrame::new(Control::Send, Address::Primary(0x01), CiField::DataSend, &data)
+            .to_bytes();
+        let frame = LongFrame::try_from_bytes(&bytes).unwrap();
+        assert_eq!(frame.data(), data.as_slice());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_round_trips_every_data_length() {
+        // Without user data, the frame is a control frame
+        let err = LongFrame::try_new(Control::Send, Address::Primary(0x01), CiField::DataSend, &[]).unwrap_err();
+        assert!(matches!(err, LongFrameEncodeError::EmptyData));
+        let bytes = ControlFrame::new(Control::Send, Address::Primary(0x01), CiField::DataSend).to_bytes();
+        assert!(matches!(LongFrame::try_from_bytes(&bytes), Err(LongFrameDecodeError::InvalidSize(9))));
+        assert!(matches!(Frame::try_from_bytes(&bytes), Ok(Frame::Control(_))));
+
+        for length in 1..=MAX_DATA_LENGTH {
+            let data: Vec<u8> = (0..length).map(|i| i as u8).collect();
+            let bytes = LongFrame::try_new(Control::Send, Address::Primary(0x01), CiField::DataSend, &data)
+                .unwrap()
+                .to_bytes();
+            assert_eq!(bytes.len(), length + 9);
+            assert_eq!(bytes[LENGTH_1_INDEX] as usize, length + 3);
+            let frame = LongFrame::try_from_bytes(&bytes).unwrap();
+            assert_eq!(frame.data(), data.as_slice());
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    proptest! {
+        #[test]
+        fn it_round_trips_any_valid_frame(
+            control in prop::sample::select(vec![0x53u8, 0x73, 0x08, 0x18, 0x28, 0x38]),
+            address: u8,
+            ci: u8,
+            data in prop::collection::vec(any::<u8>(), 1..=MAX_DATA_LENGTH),
+        ) {
+            let control = ControlField::try_from(control).unwrap();
+            let frame = LongFrame::try_new(control, address.into(), ci.into(), &data).unwrap();
+            let bytes = frame.to_bytes();
+            prop_assert_eq!(bytes.len(), data.len() + 9);
+
+            let decoded = LongFrame::try_from_bytes(&bytes).unwrap();
+            prop_assert_eq!(decoded.data(), data.as_slice());
+            prop_assert_eq!(decoded.to_bytes(), bytes);
+        }
+
+        #[test]
+        fn it_rejects_any_oversize_data(length in (MAX_DATA_LENGTH + 1)..1024usize) {
+            let data = vec![0u8; length];
+            let result = LongFrame::try_new(Control::Send, Address::Primary(0x01), CiField::DataSend, &data);
+            prop_assert!(matches!(result, Err(LongFrameEncodeError::DataTooLong(l)) if l == length));
+        }
+    }
 }
@@ -1,43 +1,110 @@
+mod control;
+mod decoder;
 mod long;
 mod short;
 mod single;
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 use thiserror::Error;
-pub use long::LongFrame;
+pub use control::ControlFrame;
+pub use decoder::FrameDecoder;
+#[cfg(feature = "tokio")]
+pub(crate) use decoder::{scan, Scan};
+#[cfg(feature = "tokio")]
+pub(crate) use long::MAX_LENGTH;
+pub use long::{LongFrame, LongFrameEncodeError, LongFrameRef, MAX_DATA_LENGTH};
 pub use short::ShortFrame;
 pub use single::SingleCharacterFrame;
 use crate::address::Address;
-use crate::control::Control;
+use crate::ci::CiField;
+use crate::control::ControlField;
+
+/// Storage of the user data of a long frame
+#[cfg(feature = "alloc")]
+pub(crate) type Payload = Vec<u8>;
+
+/// Storage of the user data of a long frame
+///
+/// Without an allocator, the user data is stored inline, with room for the
+/// largest payload a long frame can carry.
+#[cfg(not(feature = "alloc"))]
+pub(crate) type Payload = heapless::Vec<u8, { long::MAX_DATA_LENGTH }>;
+
+/// Copy user data into the payload storage of a frame
+///
+/// # Panics
+///
+/// Panics without an allocator if the user data exceeds the capacity of a
+/// long frame.
+pub(crate) fn payload_from_slice(data: &[u8]) -> Payload {
+    #[cfg(feature = "alloc")]
+    {
+        data.to_vec()
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    {
+        Payload::from_slice(data).expect("user data exceeds the capacity of a long frame")
+    }
+}
 
 /// Trait for M-Bus frames
 pub trait Encodable: Sized {
     /// Error type for frame parsing
     type Error;
 
+    /// Get the number of bytes of the encoded frame
+    fn encoded_len(&self) -> usize;
+
+    /// Encode the frame into a byte buffer, returning the number of bytes written
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError>;
+
     /// Convert the frame to a byte vector
-    fn to_bytes(&self) -> Vec<u8>;
+    #[cfg(feature = "alloc")]
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = alloc::vec![0; self.encoded_len()];
+        self.encode_into(&mut bytes)
+            .expect("buffer is sized to the encoded length");
+        bytes
+    }
 
     /// Create a frame from a byte slice
     fn try_from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>;
 }
 
+/// Get the part of a buffer that an encoded frame of the given length fills
+///
+/// Fails if the buffer is too small to hold the frame.
+pub(crate) fn output_buffer(buf: &mut [u8], length: usize) -> Result<&mut [u8], EncodeError> {
+    let available = buf.len();
+    buf.get_mut(..length)
+        .ok_or(EncodeError::BufferTooSmall(length, available))
+}
+
 pub trait FrameWithControl: Sized {
     /// Set the frame count bit (FCB) of the frame's control field
     fn with_frame_count_bit(&self, fcb: bool) -> Self;
 }
 
 /// Generic M-Bus frame
+///
+/// Without an allocator, long frames hold their user data inline, which makes
+/// them much larger than the other variants.
 #[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "alloc"), allow(clippy::large_enum_variant))]
 pub enum Frame {
     Short(ShortFrame),
     Long(LongFrame),
+    Control(ControlFrame),
     Single(SingleCharacterFrame),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum FrameType {
     Short,
     Long,
+    Control,
     Single,
 }
 
@@ -46,12 +113,40 @@ impl Frame {
         Frame::Single(frame)
     }
 
-    pub fn new_short(control: Control, address: Address) -> Self {
+    pub fn new_short(control: impl Into<ControlField>, address: Address) -> Self {
         Frame::Short(ShortFrame::new(control, address))
     }
 
-    pub fn new_long(control: Control, address: Address, data: Vec<u8>) -> Self {
-        Frame::Long(LongFrame::new(control, address, &data))
+    /// Create a long frame
+    ///
+    /// # Panics
+    ///
+    /// Panics if the user data is empty or too long, see [`LongFrame::new`].
+    /// Use [`Frame::new_control`] for frames without user data.
+    pub fn new_long(
+        control: impl Into<ControlField>,
+        address: Address,
+        ci: CiField,
+        data: &[u8],
+    ) -> Self {
+        Frame::Long(LongFrame::new(control, address, ci, data))
+    }
+
+    /// Try creating a long frame
+    ///
+    /// Fails if the user data is empty or too long, see
+    /// [`LongFrame::try_new`].
+    pub fn try_new_long(
+        control: impl Into<ControlField>,
+        address: Address,
+        ci: CiField,
+        data: &[u8],
+    ) -> Result<Self, LongFrameEncodeError> {
+        Ok(Frame::Long(LongFrame::try_new(control, address, ci, data)?))
+    }
+
+    pub fn new_control(control: impl Into<ControlField>, address: Address, ci: CiField) -> Self {
+        Frame::Control(ControlFrame::new(control, address, ci))
     }
 
     /// Parse an M-Bus frame from a byte slice
@@ -59,23 +154,67 @@ impl Frame {
         match Self::detect_type_from_bytes(bytes)? {
             FrameType::Short => Ok(Frame::Short(ShortFrame::try_from_bytes(bytes)?)),
             FrameType::Long => Ok(Frame::Long(LongFrame::try_from_bytes(bytes)?)),
+            FrameType::Control => Ok(Frame::Control(ControlFrame::try_from_bytes(bytes)?)),
             FrameType::Single => Ok(Frame::Single(SingleCharacterFrame::try_from_bytes(bytes)?)),
         }
     }
 
+    /// Get the number of bytes of the encoded frame
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            Frame::Short(frame) => frame.encoded_len(),
+            Frame::Long(frame) => frame.encoded_len(),
+            Frame::Control(frame) => frame.encoded_len(),
+            Frame::Single(frame) => frame.encoded_len(),
+        }
+    }
+
+    /// Encode the frame into a byte buffer, returning the number of bytes written
+    pub fn encode_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        match self {
+            Frame::Short(frame) => frame.encode_into(buf),
+            Frame::Long(frame) => frame.encode_into(buf),
+            Frame::Control(frame) => frame.encode_into(buf),
+            Frame::Single(frame) => frame.encode_into(buf),
+        }
+    }
+
     /// Convert the frame to a byte vector
+    #[cfg(feature = "alloc")]
     pub fn to_bytes(&self) -> Vec<u8> {
         match self {
             Frame::Short(frame) => frame.to_bytes(),
             Frame::Long(frame) => frame.to_bytes(),
+            Frame::Control(frame) => frame.to_bytes(),
             Frame::Single(frame) => frame.to_bytes(),
         }
     }
 
+    /// Get the control field of the frame, if it has one
+    pub fn control(&self) -> Option<ControlField> {
+        match self {
+            Frame::Short(frame) => Some(frame.control()),
+            Frame::Long(frame) => Some(frame.control()),
+            Frame::Control(frame) => Some(frame.control()),
+            Frame::Single(_) => None,
+        }
+    }
+
+    /// Get the address of the frame, if it has one
+    pub fn address(&self) -> Option<Address> {
+        match self {
+            Frame::Short(frame) => Some(frame.address()),
+            Frame::Long(frame) => Some(frame.address()),
+            Frame::Control(frame) => Some(frame.address()),
+            Frame::Single(_) => None,
+        }
+    }
+
     pub fn get_type(&self) -> FrameType {
         match self {
             Frame::Short(_) => FrameType::Short,
             Frame::Long(_) => FrameType::Long,
+            Frame::Control(_) => FrameType::Control,
             Frame::Single(_) => FrameType::Single,
         }
     }
@@ -87,6 +226,8 @@ impl Frame {
 
         match bytes[0] {
             0x10 => Ok(FrameType::Short),
+            // Control frames are long frames that only carry a CI field
+            0x68 if bytes.get(1) == Some(&control::LENGTH_FIELD) => Ok(FrameType::Control),
             0x68 => Ok(FrameType::Long),
             0xE5 | 0xA2 => Ok(FrameType::Single),
             _ => Err(FrameDetectionError::UnknownFrameType(bytes[0])),
@@ -94,11 +235,56 @@ impl Frame {
     }
 }
 
+/// Borrowed generic M-Bus frame
+///
+/// A zero-copy view of an M-Bus frame, where the user data of long frames is
+/// borrowed from the input buffer. Frames without user data are small enough
+/// to be held by value.
+#[derive(Debug, Clone)]
+pub enum FrameRef<'a> {
+    Short(ShortFrame),
+    Long(LongFrameRef<'a>),
+    Control(ControlFrame),
+    Single(SingleCharacterFrame),
+}
+
+impl<'a> FrameRef<'a> {
+    /// Parse a borrowed M-Bus frame from a byte slice
+    pub fn try_from_bytes(bytes: &'a [u8]) -> Result<Self, FrameError> {
+        match Frame::detect_type_from_bytes(bytes)? {
+            FrameType::Short => Ok(FrameRef::Short(ShortFrame::try_from_bytes(bytes)?)),
+            FrameType::Long => Ok(FrameRef::Long(LongFrameRef::try_from_bytes(bytes)?)),
+            FrameType::Control => Ok(FrameRef::Control(ControlFrame::try_from_bytes(bytes)?)),
+            FrameType::Single => Ok(FrameRef::Single(SingleCharacterFrame::try_from_bytes(bytes)?)),
+        }
+    }
+
+    /// Copy the borrowed frame into an owned [`Frame`]
+    pub fn to_owned(&self) -> Frame {
+        match self {
+            FrameRef::Short(frame) => Frame::Short(frame.clone()),
+            FrameRef::Long(frame) => Frame::Long(frame.to_owned()),
+            FrameRef::Control(frame) => Frame::Control(frame.clone()),
+            FrameRef::Single(frame) => Frame::Single(*frame),
+        }
+    }
+
+    pub fn get_type(&self) -> FrameType {
+        match self {
+            FrameRef::Short(_) => FrameType::Short,
+            FrameRef::Long(_) => FrameType::Long,
+            FrameRef::Control(_) => FrameType::Control,
+            FrameRef::Single(_) => FrameType::Single,
+        }
+    }
+}
+
 impl FrameWithControl for Frame {
     fn with_frame_count_bit(&self, fcb: bool) -> Self {
         match self {
             Frame::Short(frame) => Frame::Short(frame.with_frame_count_bit(fcb)),
             Frame::Long(frame) => Frame::Long(frame.with_frame_count_bit(fcb)),
+            Frame::Control(frame) => Frame::Control(frame.with_frame_count_bit(fcb)),
             Frame::Single(_) => self.clone(),
         }
     }
@@ -112,6 +298,13 @@ pub enum FrameDetectionError {
     UnknownFrameType(u8),
 }
 
+/// Errors that can occur when encoding an M-Bus frame
+#[derive(Error, Debug)]
+pub enum EncodeError {
+    #[error("buffer too small for frame, expected at least {0} bytes, got {1}")]
+    BufferTooSmall(usize, usize),
+}
+
 #[derive(Error, Debug)]
 pub enum FrameError {
     #[error("frame detection failed: {0}")]
@@ -120,6 +313,8 @@ pub enum FrameError {
     ShortFrame(#[from] short::ShortFrameDecodeError),
     #[error("long frame parsing failed: {0}")]
     LongFrame(#[from] long::LongFrameDecodeError),
+    #[error("control frame parsing failed: {0}")]
+    ControlFrame(#[from] control::ControlFrameDecodeError),
     #[error("single character frame parsing failed: {0}")]
     SingleCharacterFrame(#[from] single::SingleCharacterFrameDecodeError),
 }
@@ -127,56 +322,124 @@ pub enum FrameError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::control::Control;
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
 
     #[test]
     fn it_detects_a_short_frame() {
-        let bytes = vec![0x10, 0x7B, 0x00, 0x7B, 0x10];
+        let bytes = [0x10, 0x7B, 0x00, 0x7B, 0x10];
         let frame_type = Frame::detect_type_from_bytes(&bytes).unwrap();
         matches!(frame_type, FrameType::Short);
     }
 
     #[test]
     fn it_detects_a_long_frame() {
-        let bytes = vec![0x68, 0x06, 0x06, 0x68, 0x53, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5A, 0x16];
+        let bytes = [0x68, 0x06, 0x06, 0x68, 0x53, 0x01, 0x00, 0x01, 0x02, 0x03, 0x5A, 0x16];
         let frame_type = Frame::detect_type_from_bytes(&bytes).unwrap();
         matches!(frame_type, FrameType::Long);
     }
 
+    #[test]
+    fn it_detects_a_control_frame() {
+        let bytes = [0x68, 0x03, 0x03, 0x68, 0x53, 0x01, 0x50, 0xA4, 0x16];
+        let frame_type = Frame::detect_type_from_bytes(&bytes).unwrap();
+        assert!(matches!(frame_type, FrameType::Control));
+    }
+
+    #[test]
+    fn it_parses_a_control_frame() {
+        let bytes = [0x68, 0x03, 0x03, 0x68, 0x53, 0x01, 0x50, 0xA4, 0x16];
+        let frame = Frame::try_from_bytes(&bytes).unwrap();
+        assert!(matches!(frame, Frame::Control(_)));
+    }
+
+    #[test]
+    fn it_parses_a_long_frame_carrying_user_data() {
+        let bytes = [0x68, 0x04, 0x04, 0x68, 0x53, 0x01, 0x51, 0x00, 0xA5, 0x16];
+        let frame = Frame::try_from_bytes(&bytes).unwrap();
+        assert!(matches!(frame, Frame::Long(_)));
+    }
+
     #[test]
     fn it_detects_a_single_character_frame() {
-        let bytes = vec![0xE5];
+        let bytes = [0xE5];
         let frame_type = Frame::detect_type_from_bytes(&bytes).unwrap();
         matches!(frame_type, FrameType::Single);
     }
 
     #[test]
     fn it_fails_to_detect_an_empty_byte_slice() {
-        let bytes = vec![];
+        let bytes: [u8; 0] = [];
         let err = Frame::detect_type_from_bytes(&bytes).unwrap_err();
         assert!(matches!(err, FrameDetectionError::Empty));
     }
 
     #[test]
     fn it_fails_to_detect_an_unknown_frame_type() {
-        let bytes = vec![0x00];
+        let bytes = [0x00];
         let err = Frame::detect_type_from_bytes(&bytes).unwrap_err();
         assert!(matches!(err, FrameDetectionError::UnknownFrameType(0x00)));
     }
 
     #[test]
     fn it_fails_to_parse_an_invalid_short_frame() {
-        let bytes = vec![0x10, 0x00, 0x00, 0x00];
+        let bytes = [0x10, 0x00, 0x00, 0x00];
         let err = Frame::try_from_bytes(&bytes).unwrap_err();
         assert!(matches!(err, FrameError::ShortFrame(_)));
     }
 
     #[test]
     fn it_fails_to_parse_an_invalid_long_frame() {
-        let bytes = vec![0x68, 0x00, 0x00, 0x68];
+        let bytes = [0x68, 0x00, 0x00, 0x68];
         let err = Frame::try_from_bytes(&bytes).unwrap_err();
         assert!(matches!(err, FrameError::LongFrame(_)));
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_parses_a_borrowed_long_frame() {
+        let bytes = vec![0x68, 0x04, 0x04, 0x68, 0x53, 0x01, 0x51, 0x00, 0xA5, 0x16];
+        let frame = FrameRef::try_from_bytes(&bytes).unwrap();
+        let FrameRef::Long(long) = frame else {
+            panic!("expected a long frame");
+        };
+        assert_eq!(long.data(), &[0x00]);
+        assert_eq!(frame.to_owned().to_bytes(), bytes);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_parses_a_borrowed_short_frame() {
+        let bytes = vec![0x10, 0x40, 0x01, 0x41, 0x16];
+        let frame = FrameRef::try_from_bytes(&bytes).unwrap();
+        assert!(matches!(frame, FrameRef::Short(_)));
+        assert_eq!(frame.to_owned().to_bytes(), bytes);
+    }
+
+    #[test]
+    fn it_fails_to_parse_an_invalid_borrowed_long_frame() {
+        let bytes = [0x68, 0x00, 0x00, 0x68];
+        let err = FrameRef::try_from_bytes(&bytes).unwrap_err();
+        assert!(matches!(err, FrameError::LongFrame(_)));
+    }
+
+    #[test]
+    fn it_encodes_a_frame_into_a_buffer() {
+        let frame = Frame::new_short(Control::Initialize, Address::Primary(1));
+        let mut buf = [0u8; 8];
+        let length = frame.encode_into(&mut buf).unwrap();
+        assert_eq!(&buf[..length], &[0x10, 0x40, 0x01, 0x41, 0x16]);
+    }
+
+    #[test]
+    fn it_fails_to_encode_a_frame_into_a_buffer_too_small() {
+        let frame = Frame::new_long(Control::Send, Address::Primary(1), CiField::DataSend, &[0x01]);
+        let mut buf = [0u8; 9];
+        let err = frame.encode_into(&mut buf).unwrap_err();
+        assert!(matches!(err, EncodeError::BufferTooSmall(10, 9)));
+    }
+
     #[test]
     fn it_creates_a_new_short_frame() {
         let frame = Frame::new_short(Control::Request, Address::Primary(1));
@@ -185,10 +448,28 @@ mod tests {
 
     #[test]
     fn it_creates_a_new_long_frame() {
-        let frame = Frame::new_long(Control::Request, Address::Primary(1), vec![0x01, 0x02, 0x03]);
+        let frame = Frame::new_long(Control::Send, Address::Primary(1), CiField::DataSend, &[0x01, 0x02, 0x03]);
         assert!(matches!(frame, Frame::Long(_)));
     }
 
+    #[test]
+    fn it_fails_to_create_a_long_frame_without_data() {
+        let err = Frame::try_new_long(Control::Send, Address::Primary(1), CiField::DataSend, &[]).unwrap_err();
+        assert!(matches!(err, LongFrameEncodeError::EmptyData));
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_creating_a_long_frame_without_data() {
+        Frame::new_long(Control::Send, Address::Primary(1), CiField::DataSend, &[]);
+    }
+
+    #[test]
+    fn it_creates_a_new_control_frame() {
+        let frame = Frame::new_control(Control::Send, Address::Primary(1), CiField::ApplicationReset);
+        assert!(matches!(frame, Frame::Control(_)));
+    }
+
     #[test]
     fn it_creates_a_new_single_character_frame() {
         let frame = Frame::new_single(SingleCharacterFrame::Ack);
@@ -1,7 +1,7 @@
-use super::{Encodable, FrameWithControl};
+use super::{output_buffer, EncodeError, Encodable, FrameWithControl};
 use crate::address::Address;
 use thiserror::Error;
-use crate::control::Control;
+use crate::control::ControlField;
 
 /// M-Bus Short Frame
 ///
@@ -21,7 +21,7 @@ pub struct ShortFrame {
     /// Control
     ///
     /// The control byte is used to indicate the type of frame.
-    control: Control,
+    control: ControlField,
 
     /// Address
     ///
@@ -60,18 +60,30 @@ const END_INDEX: usize = 4;
 /// Implementation of the M-Bus short frame
 impl ShortFrame {
     /// Create a new M-Bus short frame
-    pub fn new(control: Control, address: Address) -> Self {
+    pub fn new(control: impl Into<ControlField>, address: Address) -> Self {
+        let control = control.into();
+
         Self {
             start: START_BYTE,
-            control: control.clone(),
-            address: address.clone(),
+            control,
+            address,
             checksum: Self::compute_checksum(control, address),
             end: END_BYTE,
         }
     }
 
+    /// Get the control field of the frame
+    pub fn control(&self) -> ControlField {
+        self.control
+    }
+
+    /// Get the address of the frame
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
     /// Compute the checksum of a long frame
-    fn compute_checksum(control: Control, address: Address) -> u8 {
+    fn compute_checksum(control: ControlField, address: Address) -> u8 {
         u8::from(control)
             .wrapping_add(address.into())
     }
@@ -80,15 +92,22 @@ impl ShortFrame {
 impl Encodable for ShortFrame {
     type Error = ShortFrameDecodeError;
 
-    /// Convert the short frame to a byte vector.
-    fn to_bytes(&self) -> Vec<u8> {
-        vec![
-            self.start,
-            self.control.into(),
-            self.address.into(),
-            self.checksum,
-            self.end,
-        ]
+    /// Get the number of bytes of the encoded short frame.
+    fn encoded_len(&self) -> usize {
+        LENGTH
+    }
+
+    /// Encode the short frame into a byte buffer.
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let buf = output_buffer(buf, LENGTH)?;
+
+        buf[START_INDEX] = self.start;
+        buf[CONTROL_INDEX] = self.control.into();
+        buf[ADDRESS_INDEX] = self.address.into();
+        buf[CHECKSUM_INDEX] = self.checksum;
+        buf[END_INDEX] = self.end;
+
+        Ok(LENGTH)
     }
 
     /// Try decoding a byte slice into a short frame.
@@ -129,9 +148,7 @@ impl Encodable for ShortFrame {
 
 impl FrameWithControl for ShortFrame {
     fn with_frame_count_bit(&self, fcb: bool) -> Self {
-        let mut new = self.clone();
-        new.control = self.control.with_frame_count_bit(fcb);
-        new
+        Self::new(self.control.with_frame_count_bit(fcb), self.address)
     }
 }
 
@@ -156,20 +173,32 @@ pub enum ShortFrameDecodeError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::control::Control;
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn it_encodes_the_frame_to_a_byte_vector() {
         let frame = ShortFrame::new(Control::Initialize, Address::Primary(0x01));
         let bytes = frame.to_bytes();
         assert_eq!(bytes, vec![0x10, 0x40, 0x01, 0x41, 0x16]);
     }
 
+    #[test]
+    fn it_encodes_the_frame_into_a_buffer() {
+        let frame = ShortFrame::new(Control::Request, Address::Primary(0x01));
+        let mut buf = [0u8; 5];
+        assert_eq!(frame.with_frame_count_bit(true).encode_into(&mut buf).unwrap(), 5);
+        assert_eq!(buf, [0x10, 0x7B, 0x01, 0x7C, 0x16]);
+    }
+
     #[test]
     fn it_decodes_a_byte_vector_into_a_frame() {
-        let bytes = vec![0x10, 0x40, 0x01, 0x41, 0x16];
+        let bytes = [0x10, 0x40, 0x01, 0x41, 0x16];
         let frame = ShortFrame::try_from_bytes(&bytes).unwrap();
         assert_eq!(frame.start, 0x10);
-        matches!(frame.control, Control::Initialize);
+        matches!(frame.control.control(), Control::Initialize);
         matches!(frame.address, Address::Primary(0x01));
         assert_eq!(frame.checksum, 0x41);
         assert_eq!(frame.end, 0x16);
@@ -177,28 +206,28 @@ mod tests {
 
     #[test]
     fn it_fails_to_decode_a_byte_vector_shorter_than_5_bytes() {
-        let bytes = vec![0x10, 0x40, 0x01, 0x41];
+        let bytes = [0x10, 0x40, 0x01, 0x41];
         let err = ShortFrame::try_from_bytes(&bytes).unwrap_err();
         assert!(matches!(err, ShortFrameDecodeError::InvalidLength(4)));
     }
 
     #[test]
     fn it_fails_to_decode_a_byte_vector_longer_than_5_bytes() {
-        let bytes = vec![0x10, 0x40, 0x01, 0x41, 0x16, 0x00];
+        let bytes = [0x10, 0x40, 0x01, 0x41, 0x16, 0x00];
         let err = ShortFrame::try_from_bytes(&bytes).unwrap_err();
         assert!(matches!(err, ShortFrameDecodeError::InvalidLength(6)));
     }
 
     #[test]
     fn it_fails_to_decode_a_byte_vector_with_invalid_start_byte() {
-        let bytes = vec![0x11, 0x40, 0x01, 0x41, 0x16];
+        let bytes = [0x11, 0x40, 0x01, 0x41, 0x16];
         let err = ShortFrame::try_from_bytes(&bytes).unwrap_err();
         assert!(matches!(err, ShortFrameDecodeError::InvalidStartByte(0x11)));
     }
 
     #[test]
     fn it_fails_to_decode_a_byte_vector_with_invalid_checksum() {
-        let bytes = vec![0x10, 0x40, 0x01, 0x42, 0x16];
+        let bytes = [0x10, 0x40, 0x01, 0x42, 0x16];
         let err = ShortFrame::try_from_bytes(&bytes).unwrap_err();
         assert!(matches!(
             err,
@@ -208,8 +237,24 @@ mod tests {
 
     #[test]
     fn it_fails_to_decode_a_byte_vector_with_invalid_end_byte() {
-        let bytes = vec![0x10, 0x40, 0x01, 0x41, 0x15];
+        let bytes = [0x10, 0x40, 0x01, 0x41, 0x15];
         let err = ShortFrame::try_from_bytes(&bytes).unwrap_err();
         assert!(matches!(err, ShortFrameDecodeError::InvalidEndByte(0x15)));
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_re_encodes_a_decoded_frame_to_the_same_bytes() {
+        let bytes = vec![0x10, 0x7B, 0x01, 0x7C, 0x16];
+        let frame = ShortFrame::try_from_bytes(&bytes).unwrap();
+        assert_eq!(frame.to_bytes(), bytes);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_updates_the_checksum_when_setting_the_frame_count_bit() {
+        let frame = ShortFrame::new(Control::Request, Address::Primary(0x01));
+        let bytes = frame.with_frame_count_bit(true).to_bytes();
+        assert_eq!(bytes, vec![0x10, 0x7B, 0x01, 0x7C, 0x16]);
+    }
 }
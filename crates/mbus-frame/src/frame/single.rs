@@ -1,4 +1,4 @@
-use super::Encodable;
+use super::{output_buffer, EncodeError, Encodable};
 use thiserror::Error;
 
 /// M-Bus Single Character Frame
@@ -21,9 +21,16 @@ pub enum SingleCharacterFrame {
 impl Encodable for SingleCharacterFrame {
     type Error = SingleCharacterFrameDecodeError;
 
-    /// Convert the single character frame to a byte vector
-    fn to_bytes(&self) -> Vec<u8> {
-        vec![*self as u8]
+    /// Get the number of bytes of the encoded single character frame
+    fn encoded_len(&self) -> usize {
+        1
+    }
+
+    /// Encode the single character frame into a byte buffer
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let buf = output_buffer(buf, 1)?;
+        buf[0] = *self as u8;
+        Ok(1)
     }
 
     /// Create a single character frame from a byte slice
@@ -53,24 +60,34 @@ pub enum SingleCharacterFrameDecodeError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn it_encodes_the_frame_to_a_byte_vector() {
         let frame = SingleCharacterFrame::Ack;
         let bytes = frame.to_bytes();
         assert_eq!(bytes, vec![0xE5]);
     }
 
+    #[test]
+    fn it_encodes_the_frame_into_a_buffer() {
+        let mut buf = [0u8; 1];
+        assert_eq!(SingleCharacterFrame::Nack.encode_into(&mut buf).unwrap(), 1);
+        assert_eq!(buf, [0xA2]);
+    }
+
     #[test]
     fn it_decodes_a_byte_slice_to_a_frame() {
-        let bytes = vec![0xE5];
+        let bytes = [0xE5];
         let frame = SingleCharacterFrame::try_from_bytes(&bytes).unwrap();
         matches!(frame, SingleCharacterFrame::Ack);
     }
 
     #[test]
     fn it_fails_to_decode_a_byte_slice_with_invalid_size() {
-        let bytes = vec![0xE5, 0x00];
+        let bytes = [0xE5, 0x00];
         let err = SingleCharacterFrame::try_from_bytes(&bytes).unwrap_err();
         assert!(matches!(
             err,
@@ -80,7 +97,7 @@ mod tests {
 
     #[test]
     fn it_fails_to_decode_a_byte_slice_with_invalid_byte() {
-        let bytes = vec![0x00];
+        let bytes = [0x00];
         let err = SingleCharacterFrame::try_from_bytes(&bytes).unwrap_err();
         assert!(matches!(
             err,
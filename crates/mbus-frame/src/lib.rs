@@ -1,4 +1,15 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod frame;
 pub mod address;
 pub mod control;
+pub mod ci;
+pub mod link;
+pub mod transport;
+pub mod wmbus;
 
+#[cfg(feature = "tokio")]
+pub mod codec;
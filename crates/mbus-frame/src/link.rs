@@ -0,0 +1,397 @@
+use crate::address::Address;
+use crate::ci::CiField;
+use crate::control::Control;
+use crate::frame::{
+    Frame, FrameError, FrameType, FrameWithControl, LongFrame, LongFrameEncodeError,
+    SingleCharacterFrame,
+};
+use crate::transport::{ReceiveError, Transport};
+use thiserror::Error;
+
+/// Default number of times a frame is repeated when the slave doesn't reply
+const DEFAULT_RETRIES: usize = 2;
+
+/// Reply expected by the master for a given frame
+enum Expected {
+    /// A single character acknowledgment (ACK)
+    Ack,
+
+    /// A long frame carrying user data (RSP-UD)
+    Response,
+}
+
+/// M-Bus Link Layer Master
+///
+/// The link master implements the master side of the link transmission
+/// procedures defined in EN 60870-5-2 and EN 13757-2 (§5), on top of any
+/// [`Transport`]:
+///
+/// - the frame count bit (FCB) is tracked for each address, and toggled only
+///   after a valid reply from the slave,
+/// - frames that aren't answered within the response timeout, or that are
+///   answered with an invalid frame, are repeated with the same FCB,
+/// - an SND-NKE resets the FCB state of the addressed slave(s).
+#[derive(Debug)]
+pub struct LinkMaster<T> {
+    /// Transport used to exchange frames with the slaves
+    transport: T,
+
+    /// Number of times a frame is repeated when the slave doesn't reply
+    retries: usize,
+
+    /// Frame count bit of the next frame sent to each address
+    fcb: [bool; 256],
+}
+
+impl<T: Transport> LinkMaster<T> {
+    /// Create a new link master on top of a transport
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            retries: DEFAULT_RETRIES,
+            fcb: [true; 256],
+        }
+    }
+
+    /// Set the number of times a frame is repeated when the slave doesn't reply
+    pub fn with_retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Get the number of times a frame is repeated when the slave doesn't reply
+    pub fn retries(&self) -> usize {
+        self.retries
+    }
+
+    /// Change the number of times a frame is repeated when the slave doesn't
+    /// reply
+    pub fn set_retries(&mut self, retries: usize) {
+        self.retries = retries;
+    }
+
+    /// Get a reference to the underlying transport
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Get a mutable reference to the underlying transport
+    pub fn transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Consume the link master, returning the underlying transport
+    pub fn into_transport(self) -> T {
+        self.transport
+    }
+
+    /// Get the frame count bit (FCB) of the next frame sent to an address
+    pub fn frame_count_bit(&self, address: Address) -> bool {
+        self.fcb[u8::from(address) as usize]
+    }
+
+    /// Initialize a slave (SND-NKE), and wait for its acknowledgment
+    ///
+    /// When sent to the broadcast address, no acknowledgment is expected and
+    /// the FCB state of every address is reset.
+    pub fn initialize(&mut self, address: Address) -> Result<(), LinkError<T::Error>> {
+        let frame = Frame::new_short(Control::Initialize, address);
+
+        if address == Address::Broadcast {
+            self.transport.send(&frame).map_err(LinkError::Transport)?;
+            self.fcb = [true; 256];
+            return Ok(());
+        }
+
+        self.transact(frame).map(|_| ())
+    }
+
+    /// Request user data from a slave (REQ-UD2), and wait for its response
+    pub fn request_user_data(&mut self, address: Address) -> Result<LongFrame, LinkError<T::Error>> {
+        match self.transact(Frame::new_short(Control::Request, address))? {
+            Frame::Long(frame) => Ok(frame),
+            frame => Err(LinkError::UnexpectedFrame(frame.get_type())),
+        }
+    }
+
+    /// Send user data to a slave (SND-UD), and wait for its acknowledgment
+    ///
+    /// Fails if the user data is empty or too long for a long frame. Use
+    /// [`LinkMaster::send_control`] to send a CI field without user data.
+    pub fn send_user_data(
+        &mut self,
+        address: Address,
+        ci: CiField,
+        data: &[u8],
+    ) -> Result<(), LinkError<T::Error>> {
+        let frame = Frame::try_new_long(Control::Send, address, ci, data)?;
+        self.transact(frame).map(|_| ())
+    }
+
+    /// Send a control frame to a slave (SND-UD with a CI field only), and
+    /// wait for its acknowledgment
+    pub fn send_control(&mut self, address: Address, ci: CiField) -> Result<(), LinkError<T::Error>> {
+        self.transact(Frame::new_control(Control::Send, address, ci))
+            .map(|_| ())
+    }
+
+    /// Send a frame to a slave and wait for its reply
+    ///
+    /// The FCB of the frame is set from the state of the addressed slave.
+    /// SND-NKE and SND-UD frames expect an acknowledgment, while REQ-UD1 and
+    /// REQ-UD2 frames expect a long frame carrying user data.
+    pub fn transact(&mut self, frame: Frame) -> Result<Frame, LinkError<T::Error>> {
+        let (Some(control), Some(address)) = (frame.control(), frame.address()) else {
+            return Err(LinkError::InvalidRequest(frame.get_type()));
+        };
+
+        let index = u8::from(address) as usize;
+        let (frame, expected) = match control.control() {
+            Control::Initialize => {
+                // The slave resets its FCB state upon SND-NKE, whether or not
+                // its acknowledgment reaches us
+                self.fcb[index] = true;
+                (frame, Expected::Ack)
+            }
+            Control::Send => (frame.with_frame_count_bit(self.fcb[index]), Expected::Ack),
+            Control::PriorityRequest | Control::Request => (
+                frame.with_frame_count_bit(self.fcb[index]),
+                Expected::Response,
+            ),
+            Control::Response => return Err(LinkError::InvalidRequest(frame.get_type())),
+        };
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            self.transport.send(&frame).map_err(LinkError::Transport)?;
+
+            let error = match self.transport.receive() {
+                Ok(reply) => {
+                    Self::check_reply(&reply, expected)?;
+                    if control.control() != Control::Initialize {
+                        self.fcb[index] = !self.fcb[index];
+                    }
+                    return Ok(reply);
+                }
+                Err(ReceiveError::Timeout) => LinkError::Timeout(attempts),
+                Err(ReceiveError::InvalidFrame(err)) => LinkError::InvalidFrame(attempts, err),
+                Err(ReceiveError::Transport(err)) => return Err(LinkError::Transport(err)),
+            };
+
+            if attempts > self.retries {
+                return Err(error);
+            }
+        }
+    }
+
+    /// Check that a reply is of the expected type
+    fn check_reply(reply: &Frame, expected: Expected) -> Result<(), LinkError<T::Error>> {
+        match (reply, expected) {
+            (Frame::Single(SingleCharacterFrame::Ack), Expected::Ack) => Ok(()),
+            (Frame::Single(SingleCharacterFrame::Nack), _) => Err(LinkError::Nack),
+            (Frame::Long(frame), Expected::Response)
+                if frame.control().control() == Control::Response =>
+            {
+                Ok(())
+            }
+            (reply, _) => Err(LinkError::UnexpectedFrame(reply.get_type())),
+        }
+    }
+}
+
+/// Errors that can occur during a link layer transaction
+#[derive(Error, Debug)]
+pub enum LinkError<E> {
+    #[error("the slave replied with a negative acknowledgment")]
+    Nack,
+    #[error("no reply from the slave after {0} attempts")]
+    Timeout(usize),
+    #[error("invalid reply from the slave after {0} attempts: {1}")]
+    InvalidFrame(usize, FrameError),
+    #[error("unexpected {0:?} frame in reply")]
+    UnexpectedFrame(FrameType),
+    #[error("{0:?} frame can't be sent as a request")]
+    InvalidRequest(FrameType),
+    #[error("failed to create frame: {0}")]
+    InvalidData(#[from] LongFrameEncodeError),
+    #[error("transport error: {0}")]
+    Transport(E),
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Transport replaying scripted replies, and recording sent frames
+    #[derive(Default)]
+    struct ScriptedTransport {
+        sent: Vec<Vec<u8>>,
+        replies: VecDeque<Result<Frame, ReceiveError<&'static str>>>,
+    }
+
+    impl ScriptedTransport {
+        fn reply(mut self, reply: Result<Frame, ReceiveError<&'static str>>) -> Self {
+            self.replies.push_back(reply);
+            self
+        }
+    }
+
+    impl Transport for ScriptedTransport {
+        type Error = &'static str;
+
+        fn send(&mut self, frame: &Frame) -> Result<(), Self::Error> {
+            self.sent.push(frame.to_bytes());
+            Ok(())
+        }
+
+        fn receive(&mut self) -> Result<Frame, ReceiveError<Self::Error>> {
+            self.replies.pop_front().unwrap_or(Err(ReceiveError::Timeout))
+        }
+    }
+
+    fn ack() -> Result<Frame, ReceiveError<&'static str>> {
+        Ok(Frame::new_single(SingleCharacterFrame::Ack))
+    }
+
+    fn response() -> Result<Frame, ReceiveError<&'static str>> {
+        Ok(Frame::new_long(
+            Control::Response,
+            Address::Primary(1),
+            CiField::VariableDataResponse,
+            &[0x00],
+        ))
+    }
+
+    #[test]
+    fn it_toggles_the_frame_count_bit_after_a_valid_reply() {
+        let transport = ScriptedTransport::default().reply(response()).reply(response());
+        let mut master = LinkMaster::new(transport);
+        master.request_user_data(Address::Primary(1)).unwrap();
+        master.request_user_data(Address::Primary(1)).unwrap();
+
+        let sent = &master.transport().sent;
+        assert_eq!(sent[0], vec![0x10, 0x7B, 0x01, 0x7C, 0x16]);
+        assert_eq!(sent[1], vec![0x10, 0x5B, 0x01, 0x5C, 0x16]);
+    }
+
+    #[test]
+    fn it_repeats_a_frame_with_the_same_frame_count_bit_after_a_timeout() {
+        let transport = ScriptedTransport::default()
+            .reply(Err(ReceiveError::Timeout))
+            .reply(response());
+        let mut master = LinkMaster::new(transport);
+        master.request_user_data(Address::Primary(1)).unwrap();
+
+        let sent = &master.transport().sent;
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0], sent[1]);
+        assert!(!master.frame_count_bit(Address::Primary(1)));
+    }
+
+    #[test]
+    fn it_repeats_a_frame_after_an_invalid_reply() {
+        let transport = ScriptedTransport::default()
+            .reply(Err(ReceiveError::InvalidFrame(
+                Frame::try_from_bytes(&[0x10, 0x08, 0x01, 0x00, 0x16]).unwrap_err(),
+            )))
+            .reply(response());
+        let mut master = LinkMaster::new(transport);
+        master.request_user_data(Address::Primary(1)).unwrap();
+        assert_eq!(master.transport().sent.len(), 2);
+    }
+
+    #[test]
+    fn it_gives_up_after_the_configured_retries() {
+        let mut master = LinkMaster::new(ScriptedTransport::default()).with_retries(1);
+        let err = master.request_user_data(Address::Primary(1)).unwrap_err();
+        assert!(matches!(err, LinkError::Timeout(2)));
+        assert_eq!(master.transport().sent.len(), 2);
+        assert!(master.frame_count_bit(Address::Primary(1)));
+    }
+
+    #[test]
+    fn it_tracks_the_frame_count_bit_per_address() {
+        let transport = ScriptedTransport::default().reply(response()).reply(response());
+        let mut master = LinkMaster::new(transport);
+        master.request_user_data(Address::Primary(1)).unwrap();
+        master.request_user_data(Address::Primary(2)).unwrap();
+
+        let sent = &master.transport().sent;
+        assert_eq!(sent[1], vec![0x10, 0x7B, 0x02, 0x7D, 0x16]);
+    }
+
+    #[test]
+    fn it_resets_the_frame_count_bit_on_initialize() {
+        let transport = ScriptedTransport::default()
+            .reply(response())
+            .reply(ack())
+            .reply(response());
+        let mut master = LinkMaster::new(transport);
+        master.request_user_data(Address::Primary(1)).unwrap();
+        master.initialize(Address::Primary(1)).unwrap();
+        master.request_user_data(Address::Primary(1)).unwrap();
+
+        let sent = &master.transport().sent;
+        assert_eq!(sent[1], vec![0x10, 0x40, 0x01, 0x41, 0x16]);
+        assert_eq!(sent[2], vec![0x10, 0x7B, 0x01, 0x7C, 0x16]);
+    }
+
+    #[test]
+    fn it_does_not_wait_for_a_reply_to_a_broadcast_initialize() {
+        let mut master = LinkMaster::new(ScriptedTransport::default().reply(response()));
+        master.request_user_data(Address::Primary(1)).unwrap();
+        master.initialize(Address::Broadcast).unwrap();
+        assert!(master.frame_count_bit(Address::Primary(1)));
+        assert_eq!(master.transport().sent.len(), 2);
+    }
+
+    #[test]
+    fn it_sends_user_data_and_waits_for_an_acknowledgment() {
+        let mut master = LinkMaster::new(ScriptedTransport::default().reply(ack()));
+        master
+            .send_user_data(Address::Primary(1), CiField::DataSend, &[0x01, 0x7A, 0x02])
+            .unwrap();
+
+        let sent = &master.transport().sent;
+        assert_eq!(
+            sent[0],
+            vec![0x68, 0x06, 0x06, 0x68, 0x73, 0x01, 0x51, 0x01, 0x7A, 0x02, 0x42, 0x16]
+        );
+    }
+
+    #[test]
+    fn it_fails_on_a_negative_acknowledgment() {
+        let transport =
+            ScriptedTransport::default().reply(Ok(Frame::new_single(SingleCharacterFrame::Nack)));
+        let mut master = LinkMaster::new(transport);
+        let err = master.initialize(Address::Primary(1)).unwrap_err();
+        assert!(matches!(err, LinkError::Nack));
+    }
+
+    #[test]
+    fn it_fails_on_an_unexpected_frame_type() {
+        let mut master = LinkMaster::new(ScriptedTransport::default().reply(ack()));
+        let err = master.request_user_data(Address::Primary(1)).unwrap_err();
+        assert!(matches!(err, LinkError::UnexpectedFrame(FrameType::Single)));
+        assert!(master.frame_count_bit(Address::Primary(1)));
+    }
+
+    #[test]
+    fn it_fails_to_send_a_single_character_frame_as_a_request() {
+        let mut master = LinkMaster::new(ScriptedTransport::default());
+        let err = master
+            .transact(Frame::new_single(SingleCharacterFrame::Ack))
+            .unwrap_err();
+        assert!(matches!(err, LinkError::InvalidRequest(FrameType::Single)));
+    }
+
+    #[test]
+    fn it_surfaces_transport_errors() {
+        let transport = ScriptedTransport::default().reply(Err(ReceiveError::Transport("unplugged")));
+        let mut master = LinkMaster::new(transport);
+        let err = master.request_user_data(Address::Primary(1)).unwrap_err();
+        assert!(matches!(err, LinkError::Transport("unplugged")));
+    }
+}
@@ -0,0 +1,38 @@
+use crate::frame::{Frame, FrameError};
+use thiserror::Error;
+
+#[cfg(feature = "serial")]
+pub mod serial;
+
+#[cfg(feature = "std")]
+pub mod simulator;
+
+#[cfg(feature = "std")]
+pub mod tcp;
+
+/// Trait for M-Bus transports
+///
+/// A transport carries frames between the master and the slaves, e.g. over a
+/// serial line or a TCP connection to a gateway. It is responsible for the
+/// timing of the bus, such as the response timeout of the slaves.
+pub trait Transport {
+    /// Error type of the underlying medium
+    type Error;
+
+    /// Send a frame
+    fn send(&mut self, frame: &Frame) -> Result<(), Self::Error>;
+
+    /// Receive the next frame, waiting at most for the response timeout
+    fn receive(&mut self) -> Result<Frame, ReceiveError<Self::Error>>;
+}
+
+/// Errors that can occur when receiving a frame
+#[derive(Error, Debug)]
+pub enum ReceiveError<E> {
+    #[error("no frame received before the response timeout")]
+    Timeout,
+    #[error("invalid frame received: {0}")]
+    InvalidFrame(#[from] FrameError),
+    #[error("transport error: {0}")]
+    Transport(E),
+}
@@ -0,0 +1,247 @@
+use super::{ReceiveError, Transport};
+use crate::ci::BaudRate;
+use crate::frame::{Frame, FrameDecoder};
+use serialport::{ClearBuffer, DataBits, FlowControl, Parity, SerialPort, StopBits};
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
+
+/// Number of bit times a slave waits before answering (one character)
+const ANSWER_DELAY_BITS: u32 = 11;
+
+/// Number of bit times a slave may take to answer, in addition to the fixed
+/// delay
+const RESPONSE_TIMEOUT_BITS: u32 = 330;
+
+/// Fixed delay a slave may take to answer, in addition to the bit times
+const RESPONSE_TIMEOUT_DELAY: Duration = Duration::from_millis(50);
+
+/// Get the response timeout of the slaves for a baud rate
+///
+/// EN 13757-2 (§5.4) allows a slave to answer within 330 bit times plus
+/// 50 ms, after an answer delay of 11 bit times.
+pub fn response_timeout(baud_rate: BaudRate) -> Duration {
+    let bits = ANSWER_DELAY_BITS + RESPONSE_TIMEOUT_BITS;
+    Duration::from_secs(bits as u64) / baud_rate.bits_per_second() + RESPONSE_TIMEOUT_DELAY
+}
+
+/// M-Bus serial transport
+///
+/// The serial transport carries frames over a serial line to an M-Bus level
+/// converter, with the 8E1 framing (8 data bits, even parity, 1 stop bit)
+/// required by EN 13757-2. Frames are received with a [`FrameDecoder`], and
+/// a reply must be complete within the response timeout of the slaves, even
+/// if line noise keeps arriving in the meantime.
+pub struct SerialTransport {
+    /// Serial port
+    port: Box<dyn SerialPort>,
+
+    /// Baud rate of the bus
+    baud_rate: BaudRate,
+
+    /// Response timeout
+    timeout: Duration,
+
+    /// Decoder for the received bytes
+    decoder: FrameDecoder,
+
+    /// Bytes received, but not yet pushed to the decoder
+    pending: Vec<u8>,
+}
+
+impl SerialTransport {
+    /// Open a serial port at the given baud rate
+    pub fn open(path: &str, baud_rate: BaudRate) -> serialport::Result<Self> {
+        let port = serialport::new(path, baud_rate.bits_per_second()).open()?;
+        Self::new(port, baud_rate)
+    }
+
+    /// Create a serial transport from an open serial port
+    ///
+    /// The port is configured with the 8E1 framing, the given baud rate and
+    /// the matching response timeout.
+    pub fn new(port: Box<dyn SerialPort>, baud_rate: BaudRate) -> serialport::Result<Self> {
+        let mut transport = Self {
+            port,
+            baud_rate,
+            timeout: response_timeout(baud_rate),
+            decoder: FrameDecoder::new(),
+            pending: Vec::new(),
+        };
+
+        transport.port.set_data_bits(DataBits::Eight)?;
+        transport.port.set_parity(Parity::Even)?;
+        transport.port.set_stop_bits(StopBits::One)?;
+        transport.port.set_flow_control(FlowControl::None)?;
+        transport.set_baud_rate(baud_rate)?;
+
+        Ok(transport)
+    }
+
+    /// Get the baud rate of the bus
+    pub fn baud_rate(&self) -> BaudRate {
+        self.baud_rate
+    }
+
+    /// Change the baud rate of the bus, and the matching response timeout
+    ///
+    /// This only changes the baud rate of the master. The slaves must be
+    /// switched first, with a control frame carrying the new baud rate.
+    pub fn set_baud_rate(&mut self, baud_rate: BaudRate) -> serialport::Result<()> {
+        self.port.set_baud_rate(baud_rate.bits_per_second())?;
+        self.set_timeout(response_timeout(baud_rate))?;
+        self.baud_rate = baud_rate;
+        Ok(())
+    }
+
+    /// Get the response timeout
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Override the response timeout, e.g. for slow level converters
+    pub fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> {
+        self.port.set_timeout(timeout)?;
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    /// Get a reference to the underlying serial port
+    pub fn port(&self) -> &dyn SerialPort {
+        self.port.as_ref()
+    }
+}
+
+impl Transport for SerialTransport {
+    type Error = io::Error;
+
+    /// Send a frame, dropping any bytes left over from previous exchanges
+    fn send(&mut self, frame: &Frame) -> Result<(), Self::Error> {
+        self.port.clear(ClearBuffer::Input)?;
+        self.decoder.reset();
+        self.pending.clear();
+
+        self.port.write_all(&frame.to_bytes())?;
+        self.port.flush()
+    }
+
+    fn receive(&mut self) -> Result<Frame, ReceiveError<Self::Error>> {
+        let mut buffer = [0; 64];
+        let deadline = Instant::now() + self.timeout;
+
+        loop {
+            // The decoder holds at most one maximum-size frame, so bytes it
+            // can't take yet are kept for the next round
+            let consumed = self.decoder.push(&self.pending);
+            self.pending.drain(..consumed);
+
+            if let Some(frame) = self.decoder.decode()? {
+                return Ok(frame);
+            }
+
+            if !self.pending.is_empty() {
+                continue;
+            }
+
+            let Some(remaining) = deadline.checked_duration_since(Instant::now()).filter(|d| !d.is_zero())
+            else {
+                return Err(ReceiveError::Timeout);
+            };
+
+            let read = self
+                .port
+                .set_timeout(remaining)
+                .map_err(io::Error::from)
+                .and_then(|_| self.port.read(&mut buffer));
+
+            match read {
+                Ok(0) => return Err(ReceiveError::Timeout),
+                Ok(length) => self.pending.extend_from_slice(&buffer[..length]),
+                Err(err) if err.kind() == io::ErrorKind::TimedOut => {
+                    return Err(ReceiveError::Timeout);
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(ReceiveError::Transport(err)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+    use crate::control::Control;
+    use serialport::TTYPort;
+
+    fn pair() -> (SerialTransport, TTYPort) {
+        let (master, slave) = TTYPort::pair().unwrap();
+        let transport = SerialTransport::new(Box::new(master), BaudRate::Baud2400).unwrap();
+        (transport, slave)
+    }
+
+    #[test]
+    fn it_computes_the_response_timeout_from_the_baud_rate() {
+        assert_eq!(response_timeout(BaudRate::Baud300), Duration::from_nanos(1_186_666_666));
+        assert_eq!(response_timeout(BaudRate::Baud2400), Duration::from_nanos(192_083_333));
+        assert_eq!(response_timeout(BaudRate::Baud38400), Duration::from_nanos(58_880_208));
+    }
+
+    #[test]
+    fn it_configures_the_baud_rate_and_timeout() {
+        let (transport, _slave) = pair();
+        assert_eq!(transport.baud_rate(), BaudRate::Baud2400);
+        assert_eq!(transport.timeout(), response_timeout(BaudRate::Baud2400));
+        assert_eq!(transport.port().baud_rate().unwrap(), 2400);
+    }
+
+    #[test]
+    fn it_sends_a_frame() {
+        let (mut transport, mut slave) = pair();
+        transport.send(&Frame::new_short(Control::Initialize, Address::Primary(0x01))).unwrap();
+
+        let mut buffer = [0; 5];
+        slave.read_exact(&mut buffer).unwrap();
+        assert_eq!(buffer, [0x10, 0x40, 0x01, 0x41, 0x16]);
+    }
+
+    #[test]
+    fn it_receives_frames() {
+        let (mut transport, mut slave) = pair();
+        slave.write_all(&[0xFF, 0xE5, 0x10, 0x40, 0x01, 0x41, 0x16]).unwrap();
+
+        assert!(matches!(transport.receive().unwrap(), Frame::Single(_)));
+        assert!(matches!(transport.receive().unwrap(), Frame::Short(_)));
+    }
+
+    #[test]
+    fn it_times_out_without_a_reply() {
+        let (mut transport, _slave) = pair();
+        transport.set_timeout(Duration::from_millis(10)).unwrap();
+        assert!(matches!(transport.receive(), Err(ReceiveError::Timeout)));
+    }
+
+    #[test]
+    fn it_times_out_while_receiving_line_noise() {
+        let (mut transport, mut slave) = pair();
+        transport.set_timeout(Duration::from_millis(20)).unwrap();
+
+        let noise = std::thread::spawn(move || {
+            for _ in 0..20 {
+                slave.write_all(&[0xFF]).unwrap();
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        });
+
+        let start = Instant::now();
+        assert!(matches!(transport.receive(), Err(ReceiveError::Timeout)));
+        assert!(start.elapsed() < Duration::from_millis(80));
+        noise.join().unwrap();
+    }
+
+    #[test]
+    fn it_reports_an_invalid_frame() {
+        let (mut transport, mut slave) = pair();
+        slave.write_all(&[0x10, 0x40, 0x01, 0x00, 0x16]).unwrap();
+        assert!(matches!(transport.receive(), Err(ReceiveError::InvalidFrame(_))));
+    }
+}
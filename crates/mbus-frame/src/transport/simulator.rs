@@ -0,0 +1,456 @@
+use super::{ReceiveError, Transport};
+use crate::address::{Address, SecondaryAddress, SECONDARY_ADDRESS_LENGTH};
+use crate::ci::CiField;
+use crate::control::{Control, ControlField};
+use crate::frame::{Frame, SingleCharacterFrame};
+use std::collections::VecDeque;
+use std::convert::Infallible;
+
+/// Fault injected in the reply to the next frame sent on a [`VirtualBus`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// The slaves don't answer
+    NoAnswer,
+
+    /// The checksum of the reply is corrupted
+    BadChecksum,
+
+    /// The reply collides with the acknowledgment of another slave
+    ///
+    /// As on a real bus, the collision only goes unnoticed when the reply is
+    /// itself an acknowledgment. Any other reply is garbled, starting with
+    /// its start byte (e.g. 0x68 & 0xE5 = 0x60).
+    Collision,
+}
+
+/// Virtual M-Bus Slave
+///
+/// A virtual slave emulates the link layer of a wired M-Bus slave, as defined
+/// in EN 13757-2 (§5):
+///
+/// - SND-NKE is acknowledged, and resets the FCB state and the telegram
+///   sequence of the slave,
+/// - REQ-UD2 is answered with the current RSP-UD telegram. When the FCB is
+///   toggled, the slave moves on to the next telegram, and when it isn't,
+///   the previous telegram is repeated,
+/// - SND-UD is acknowledged, and its user data is recorded once, even when
+///   repeated with the same FCB,
+/// - a CI 0x52 selection sent to the secondary addressing address (253)
+///   selects the slave if its secondary address matches, and deselects it
+///   otherwise.
+#[derive(Debug, Clone)]
+pub struct VirtualSlave {
+    /// Primary address of the slave
+    primary: u8,
+
+    /// Secondary address of the slave
+    secondary: SecondaryAddress,
+
+    /// RSP-UD telegrams sent in turn, as CI field and user data
+    telegrams: Vec<(CiField, Vec<u8>)>,
+
+    /// Index of the current telegram
+    telegram: usize,
+
+    /// Whether the current telegram was sent since the last reset
+    sent: bool,
+
+    /// FCB of the last frame with a valid FCB, since the last reset
+    fcb: Option<bool>,
+
+    /// Whether the slave is selected for secondary addressing
+    selected: bool,
+
+    /// User data received with SND-UD frames
+    received: Vec<(CiField, Vec<u8>)>,
+}
+
+impl VirtualSlave {
+    /// Create a new virtual slave
+    ///
+    /// Until telegrams are added, the slave answers with a variable data
+    /// response carrying only the fixed data header.
+    pub fn new(primary: u8, secondary: SecondaryAddress) -> Self {
+        Self {
+            primary,
+            secondary,
+            telegrams: Vec::new(),
+            telegram: 0,
+            sent: false,
+            fcb: None,
+            selected: false,
+            received: Vec::new(),
+        }
+    }
+
+    /// Add an RSP-UD telegram, sent after the previous ones
+    pub fn with_response(mut self, ci: CiField, data: &[u8]) -> Self {
+        self.telegrams.push((ci, data.to_vec()));
+        self
+    }
+
+    /// Get the primary address of the slave
+    pub fn primary_address(&self) -> u8 {
+        self.primary
+    }
+
+    /// Get the secondary address of the slave
+    pub fn secondary_address(&self) -> SecondaryAddress {
+        self.secondary
+    }
+
+    /// Check whether the slave is selected for secondary addressing
+    pub fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    /// Get the user data received with SND-UD frames, with their CI field
+    pub fn received(&self) -> &[(CiField, Vec<u8>)] {
+        &self.received
+    }
+
+    /// Check whether a frame is addressed to the slave
+    fn is_addressed(&self, address: Address) -> bool {
+        match address {
+            Address::Secondary => self.selected,
+            Address::Broadcast | Address::Diagnosis => true,
+            address => u8::from(address) == self.primary,
+        }
+    }
+
+    /// Check whether a frame repeats the previous one, and record its FCB
+    fn is_repetition(&mut self, control: ControlField) -> bool {
+        match (control.frame_count_valid(), control.frame_count_bit()) {
+            (Some(true), Some(fcb)) => self.fcb.replace(fcb) == Some(fcb),
+            _ => false,
+        }
+    }
+
+    /// Reset the FCB state and the telegram sequence
+    fn reset(&mut self) {
+        self.fcb = None;
+        self.telegram = 0;
+        self.sent = false;
+    }
+
+    /// Handle a frame sent by the master, returning the reply, if any
+    fn handle(&mut self, frame: &Frame) -> Option<Frame> {
+        let (Some(control), Some(address)) = (frame.control(), frame.address()) else {
+            return None;
+        };
+
+        // The selection is handled by every slave, to deselect the others
+        if let (Address::Secondary, Frame::Long(frame)) = (address, frame)
+            && frame.ci() == CiField::SelectSlave
+        {
+            let pattern = <&[u8; SECONDARY_ADDRESS_LENGTH]>::try_from(frame.data())
+                .map(SecondaryAddress::from_bytes);
+            self.selected = pattern.is_ok_and(|pattern| pattern.matches(&self.secondary));
+            return self.selected.then(ack);
+        }
+
+        if !self.is_addressed(address) {
+            return None;
+        }
+
+        let reply = match control.control() {
+            Control::Initialize => {
+                self.reset();
+                if address == Address::Secondary {
+                    self.selected = false;
+                }
+                Some(ack())
+            }
+            Control::Request => {
+                if !self.is_repetition(control) && self.sent {
+                    self.telegram = (self.telegram + 1) % self.telegrams.len().max(1);
+                }
+                self.sent = true;
+                Some(self.response())
+            }
+            Control::Send => {
+                if !self.is_repetition(control) {
+                    match frame {
+                        Frame::Long(frame) => self.received.push((frame.ci(), frame.data().to_vec())),
+                        Frame::Control(frame) => self.received.push((frame.ci(), Vec::new())),
+                        _ => {}
+                    }
+                }
+                Some(ack())
+            }
+            Control::PriorityRequest => Some(ack()),
+            Control::Response => None,
+        };
+
+        // Slaves don't answer broadcasts
+        reply.filter(|_| address != Address::Broadcast)
+    }
+
+    /// Get the RSP-UD frame of the current telegram
+    fn response(&self) -> Frame {
+        let address = Address::from(self.primary);
+
+        match self.telegrams.get(self.telegram) {
+            Some((ci, data)) => Frame::new_long(Control::Response, address, *ci, data),
+            None => {
+                let mut header = self.secondary.to_bytes().to_vec();
+                header.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+                Frame::new_long(Control::Response, address, CiField::VariableDataResponse, &header)
+            }
+        }
+    }
+}
+
+/// Acknowledgment frame
+fn ack() -> Frame {
+    Frame::new_single(SingleCharacterFrame::Ack)
+}
+
+/// Virtual M-Bus
+///
+/// The virtual bus connects a master to any number of [`VirtualSlave`]s
+/// in-process, behind the same [`Transport`] trait as the serial and TCP
+/// transports. When several slaves answer the same frame, their replies are
+/// overlaid as on a real bus, which usually garbles them.
+///
+/// ```
+/// use mbus_frame::address::{Address, SecondaryAddress};
+/// use mbus_frame::link::LinkMaster;
+/// use mbus_frame::transport::simulator::{VirtualBus, VirtualSlave};
+///
+/// let slave = VirtualSlave::new(1, SecondaryAddress::from_raw(0x12345678, 0x4024, 0x01, 0x07));
+/// let mut master = LinkMaster::new(VirtualBus::new().with_slave(slave));
+///
+/// master.initialize(Address::Primary(1)).unwrap();
+/// let response = master.request_user_data(Address::Primary(1)).unwrap();
+/// assert_eq!(&response.data()[..4], &[0x78, 0x56, 0x34, 0x12]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VirtualBus {
+    /// Slaves connected to the bus
+    slaves: Vec<VirtualSlave>,
+
+    /// Faults injected in the next replies
+    faults: VecDeque<Fault>,
+
+    /// Bytes of the reply to the last frame sent
+    reply: Vec<u8>,
+
+    /// Frames sent by the master
+    sent: Vec<Frame>,
+}
+
+impl VirtualBus {
+    /// Create a new virtual bus without slaves
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connect a slave to the bus
+    pub fn with_slave(mut self, slave: VirtualSlave) -> Self {
+        self.slaves.push(slave);
+        self
+    }
+
+    /// Get the slaves connected to the bus
+    pub fn slaves(&self) -> &[VirtualSlave] {
+        &self.slaves
+    }
+
+    /// Get the frames sent by the master
+    pub fn sent(&self) -> &[Frame] {
+        &self.sent
+    }
+
+    /// Inject a fault in the reply to the next frame sent
+    ///
+    /// Faults are applied in order, one per frame sent.
+    pub fn inject(&mut self, fault: Fault) {
+        self.faults.push_back(fault);
+    }
+}
+
+impl Transport for VirtualBus {
+    type Error = Infallible;
+
+    fn send(&mut self, frame: &Frame) -> Result<(), Self::Error> {
+        let replies: Vec<Vec<u8>> = self
+            .slaves
+            .iter_mut()
+            .filter_map(|slave| slave.handle(frame))
+            .map(|reply| reply.to_bytes())
+            .collect();
+
+        let mut reply = overlay(&replies);
+        match self.faults.pop_front() {
+            Some(Fault::NoAnswer) => reply.clear(),
+            Some(Fault::BadChecksum) if reply.len() > 2 => {
+                let checksum = reply.len() - 2;
+                reply[checksum] = reply[checksum].wrapping_add(1);
+            }
+            Some(Fault::BadChecksum) => reply.iter_mut().for_each(|byte| *byte ^= 0x01),
+            Some(Fault::Collision) if !reply.is_empty() => reply = overlay(&[reply, ack().to_bytes()]),
+            _ => {}
+        }
+
+        self.reply = reply;
+        self.sent.push(frame.clone());
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<Frame, ReceiveError<Self::Error>> {
+        if self.reply.is_empty() {
+            return Err(ReceiveError::Timeout);
+        }
+
+        let reply = std::mem::take(&mut self.reply);
+        Ok(Frame::try_from_bytes(&reply)?)
+    }
+}
+
+/// Overlay replies sent at the same time by several slaves
+///
+/// The bus is idle at mark (1) level, so overlapping characters combine with
+/// a bitwise AND, and the longest reply sets the length. As on a real bus,
+/// nearly identical replies may combine into a valid frame.
+fn overlay(replies: &[Vec<u8>]) -> Vec<u8> {
+    let length = replies.iter().map(Vec::len).max().unwrap_or(0);
+
+    (0..length)
+        .map(|index| {
+            replies
+                .iter()
+                .map(|reply| reply.get(index).copied().unwrap_or(0xFF))
+                .fold(0xFF, |acc, byte| acc & byte)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::link::{LinkError, LinkMaster};
+
+    const SECONDARY: [u8; 8] = [0x78, 0x56, 0x34, 0x12, 0x24, 0x40, 0x01, 0x07];
+
+    fn slave(primary: u8, id: u32) -> VirtualSlave {
+        VirtualSlave::new(primary, SecondaryAddress::from_raw(id, 0x4024, 0x01, 0x07))
+    }
+
+    fn request(fcb: bool) -> Frame {
+        Frame::new_short(ControlField::new(Control::Request).with_frame_count_bit(fcb), Address::Primary(1))
+    }
+
+    fn exchange(bus: &mut VirtualBus, frame: &Frame) -> Result<Frame, ReceiveError<Infallible>> {
+        bus.send(frame).unwrap();
+        bus.receive()
+    }
+
+    #[test]
+    fn it_acknowledges_an_snd_nke() {
+        let mut master = LinkMaster::new(VirtualBus::new().with_slave(slave(1, 0x12345678)));
+        master.initialize(Address::Primary(1)).unwrap();
+        assert!(matches!(master.initialize(Address::Primary(2)), Err(LinkError::Timeout(3))));
+    }
+
+    #[test]
+    fn it_answers_a_req_ud2_with_the_default_response() {
+        let mut master = LinkMaster::new(VirtualBus::new().with_slave(slave(1, 0x12345678)));
+        let response = master.request_user_data(Address::Primary(1)).unwrap();
+        assert_eq!(response.ci(), CiField::VariableDataResponse);
+        assert_eq!(&response.data()[..8], SECONDARY);
+    }
+
+    #[test]
+    fn it_repeats_the_telegram_when_the_fcb_is_not_toggled() {
+        let slave = slave(1, 0x12345678)
+            .with_response(CiField::VariableDataResponse, &[0x01])
+            .with_response(CiField::VariableDataResponse, &[0x02]);
+        let mut bus = VirtualBus::new().with_slave(slave);
+
+        let data = |frame: Frame| match frame {
+            Frame::Long(frame) => frame.data().to_vec(),
+            frame => panic!("expected a long frame, got {frame:?}"),
+        };
+
+        assert_eq!(data(exchange(&mut bus, &request(true)).unwrap()), [0x01]);
+        assert_eq!(data(exchange(&mut bus, &request(true)).unwrap()), [0x01]);
+        assert_eq!(data(exchange(&mut bus, &request(false)).unwrap()), [0x02]);
+        assert_eq!(data(exchange(&mut bus, &request(true)).unwrap()), [0x01]);
+
+        // SND-NKE restarts the telegram sequence
+        let nke = Frame::new_short(Control::Initialize, Address::Primary(1));
+        exchange(&mut bus, &nke).unwrap();
+        assert_eq!(data(exchange(&mut bus, &request(false)).unwrap()), [0x01]);
+    }
+
+    #[test]
+    fn it_records_user_data_once_when_repeated() {
+        let mut bus = VirtualBus::new().with_slave(slave(1, 0x12345678));
+        let frame = Frame::new_long(
+            ControlField::new(Control::Send).with_frame_count_bit(true),
+            Address::Primary(1),
+            CiField::DataSend,
+            &[0x01, 0x7A, 0x05],
+        );
+
+        assert!(matches!(exchange(&mut bus, &frame).unwrap(), Frame::Single(_)));
+        assert!(matches!(exchange(&mut bus, &frame).unwrap(), Frame::Single(_)));
+        assert_eq!(bus.slaves()[0].received(), &[(CiField::DataSend, vec![0x01, 0x7A, 0x05])]);
+    }
+
+    #[test]
+    fn it_answers_when_selected_by_secondary_address() {
+        let bus = VirtualBus::new()
+            .with_slave(slave(0, 0x12345678))
+            .with_slave(slave(0, 0x87654321));
+        let mut master = LinkMaster::new(bus);
+
+        master
+            .send_user_data(Address::Secondary, CiField::SelectSlave, &SECONDARY)
+            .unwrap();
+        let slaves = master.transport().slaves();
+        assert!(slaves[0].is_selected());
+        assert!(!slaves[1].is_selected());
+
+        let response = master.request_user_data(Address::Secondary).unwrap();
+        assert_eq!(&response.data()[..8], SECONDARY);
+
+        // SND-NKE to the secondary addressing address deselects the slave
+        master.initialize(Address::Secondary).unwrap();
+        assert!(!master.transport().slaves()[0].is_selected());
+    }
+
+    #[test]
+    fn it_garbles_the_replies_of_slaves_sharing_an_address() {
+        let bus = VirtualBus::new()
+            .with_slave(slave(1, 0x12345678))
+            .with_slave(slave(1, 0x87654321));
+        let mut master = LinkMaster::new(bus).with_retries(0);
+
+        // Simultaneous acknowledgments overlap into a valid one
+        master.initialize(Address::Primary(1)).unwrap();
+        assert!(matches!(
+            master.request_user_data(Address::Primary(1)),
+            Err(LinkError::InvalidFrame(1, _))
+        ));
+    }
+
+    #[test]
+    fn it_injects_faults() {
+        let mut bus = VirtualBus::new().with_slave(slave(1, 0x12345678));
+        bus.inject(Fault::NoAnswer);
+        bus.inject(Fault::BadChecksum);
+        bus.inject(Fault::Collision);
+        let mut master = LinkMaster::new(bus).with_retries(3);
+
+        // The fourth attempt goes through
+        master.request_user_data(Address::Primary(1)).unwrap();
+        assert_eq!(master.transport().sent().len(), 4);
+
+        let mut bus = master.into_transport();
+        bus.inject(Fault::BadChecksum);
+        let reply = exchange(&mut bus, &Frame::new_short(Control::Initialize, Address::Primary(1)));
+        assert!(matches!(reply, Err(ReceiveError::InvalidFrame(_))));
+    }
+}
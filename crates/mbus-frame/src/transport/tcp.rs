@@ -0,0 +1,407 @@
+use super::{ReceiveError, Transport};
+use crate::frame::{Frame, FrameDecoder};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default time to wait for the connection to the gateway
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default time to wait for a reply
+///
+/// This is longer than the response timeout of the slaves on a serial line,
+/// as gateways buffer the bytes they receive before forwarding them.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Default idle time between the end of an exchange and the next frame
+pub const DEFAULT_IDLE_TIME: Duration = Duration::from_millis(100);
+
+/// M-Bus TCP transport
+///
+/// The TCP transport carries frames to a serial-to-TCP M-Bus gateway in
+/// transparent mode, which forwards the bytes to and from the bus as is.
+/// Frames are reassembled with a [`FrameDecoder`], as the gateway may split
+/// them over several TCP segments.
+///
+/// The connection is opened on the first frame sent. When it fails, it's
+/// dropped, and opened again on the next frame.
+pub struct TcpTransport {
+    /// Address of the gateway
+    address: SocketAddr,
+
+    /// Connection to the gateway, if open
+    stream: Option<TcpStream>,
+
+    /// Time to wait for the connection to the gateway
+    connect_timeout: Duration,
+
+    /// Time to wait for a reply
+    timeout: Duration,
+
+    /// Idle time between the end of an exchange and the next frame
+    idle_time: Duration,
+
+    /// End of the last exchange
+    last_exchange: Option<Instant>,
+
+    /// Decoder for the received bytes
+    decoder: FrameDecoder,
+
+    /// Bytes received, but not yet pushed to the decoder
+    pending: Vec<u8>,
+}
+
+impl TcpTransport {
+    /// Create a new TCP transport to a gateway, without connecting yet
+    pub fn new(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let address = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address to connect to"))?;
+
+        Ok(Self {
+            address,
+            stream: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            timeout: DEFAULT_TIMEOUT,
+            idle_time: DEFAULT_IDLE_TIME,
+            last_exchange: None,
+            decoder: FrameDecoder::new(),
+            pending: Vec::new(),
+        })
+    }
+
+    /// Create a new TCP transport and connect to the gateway
+    pub fn connect(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let mut transport = Self::new(address)?;
+        transport.reconnect()?;
+        Ok(transport)
+    }
+
+    /// Set the time to wait for the connection to the gateway
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Set the time to wait for a reply
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the idle time between the end of an exchange and the next frame
+    pub fn with_idle_time(mut self, idle_time: Duration) -> Self {
+        self.idle_time = idle_time;
+        self
+    }
+
+    /// Get the address of the gateway
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Get the time to wait for a reply
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Get the idle time between the end of an exchange and the next frame
+    pub fn idle_time(&self) -> Duration {
+        self.idle_time
+    }
+
+    /// Check whether the connection to the gateway is open
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Open a new connection to the gateway, dropping the current one
+    pub fn reconnect(&mut self) -> io::Result<()> {
+        self.disconnect();
+
+        let stream = TcpStream::connect_timeout(&self.address, self.connect_timeout)?;
+        stream.set_nodelay(true)?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    /// Close the connection to the gateway
+    pub fn disconnect(&mut self) {
+        self.stream = None;
+        self.decoder.reset();
+        self.pending.clear();
+    }
+
+    /// Get the connection to the gateway, opening it if needed
+    fn stream(&mut self) -> io::Result<&mut TcpStream> {
+        if self.stream.is_none() {
+            self.reconnect()?;
+        }
+
+        Ok(self.stream.as_mut().expect("connection was just opened"))
+    }
+
+    /// Wait for the idle time since the end of the last exchange
+    fn wait_idle(&self) {
+        if let Some(elapsed) = self.last_exchange.map(|instant| instant.elapsed())
+            && elapsed < self.idle_time
+        {
+            thread::sleep(self.idle_time - elapsed);
+        }
+    }
+
+    /// Drop bytes left over from previous exchanges, such as late replies
+    fn drain(&mut self) -> io::Result<()> {
+        self.decoder.reset();
+        self.pending.clear();
+
+        let stream = self.stream()?;
+        stream.set_nonblocking(true)?;
+
+        let mut buffer = [0; 256];
+        let result = loop {
+            match stream.read(&mut buffer) {
+                Ok(0) => break Err(io::ErrorKind::ConnectionAborted.into()),
+                Ok(_) => {}
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break Ok(()),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => break Err(err),
+            }
+        };
+
+        stream.set_nonblocking(false)?;
+        result
+    }
+
+    /// Drop the connection after an I/O error, so that the next frame opens
+    /// a new one
+    fn fail<T>(&mut self, err: io::Error) -> io::Result<T> {
+        self.disconnect();
+        Err(err)
+    }
+}
+
+impl Transport for TcpTransport {
+    type Error = io::Error;
+
+    /// Send a frame, once the idle time has passed since the last exchange
+    fn send(&mut self, frame: &Frame) -> Result<(), Self::Error> {
+        self.wait_idle();
+
+        if let Err(err) = self.drain() {
+            // The gateway closed the connection since the last exchange
+            self.disconnect();
+            if err.kind() != io::ErrorKind::ConnectionAborted {
+                return Err(err);
+            }
+        }
+
+        let bytes = frame.to_bytes();
+        let stream = self.stream()?;
+        if let Err(err) = stream.write_all(&bytes).and_then(|_| stream.flush()) {
+            return self.fail(err);
+        }
+
+        self.last_exchange = Some(Instant::now());
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<Frame, ReceiveError<Self::Error>> {
+        let mut buffer = [0; 256];
+        let timeout = self.timeout;
+        let deadline = Instant::now() + timeout;
+
+        let result = loop {
+            // The decoder holds at most one maximum-size frame, so bytes it
+            // can't take yet are kept for the next round
+            let consumed = self.decoder.push(&self.pending);
+            self.pending.drain(..consumed);
+
+            match self.decoder.decode() {
+                Ok(Some(frame)) => break Ok(frame),
+                Ok(None) => {}
+                Err(err) => break Err(err.into()),
+            }
+
+            if !self.pending.is_empty() {
+                continue;
+            }
+
+            let Some(remaining) = deadline.checked_duration_since(Instant::now()).filter(|d| !d.is_zero())
+            else {
+                break Err(ReceiveError::Timeout);
+            };
+
+            let stream = self.stream().map_err(ReceiveError::Transport)?;
+            let read = stream
+                .set_read_timeout(Some(remaining))
+                .and_then(|_| stream.read(&mut buffer));
+
+            match read {
+                Ok(0) => {
+                    let err = io::ErrorKind::ConnectionAborted.into();
+                    break self.fail(err).map_err(ReceiveError::Transport);
+                }
+                Ok(length) => self.pending.extend_from_slice(&buffer[..length]),
+                Err(err)
+                    if matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) =>
+                {
+                    break Err(ReceiveError::Timeout);
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => break self.fail(err).map_err(ReceiveError::Transport),
+            }
+        };
+
+        self.last_exchange = Some(Instant::now());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+    use crate::control::Control;
+    use std::net::TcpListener;
+
+    const ACK: [u8; 1] = [0xE5];
+    const SND_NKE: [u8; 5] = [0x10, 0x40, 0x01, 0x41, 0x16];
+    const RSP_UD: [u8; 12] = [
+        0x68, 0x06, 0x06, 0x68, 0x08, 0x01, 0x72, 0x01, 0x02, 0x03, 0x81, 0x16,
+    ];
+
+    /// Start a gateway accepting the given number of connections, and
+    /// running the given handler on each of them
+    fn gateway(
+        connections: usize,
+        handler: impl Fn(usize, TcpStream) + Send + 'static,
+    ) -> (SocketAddr, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let handle = thread::spawn(move || {
+            for index in 0..connections {
+                let (stream, _) = listener.accept().unwrap();
+                handler(index, stream);
+            }
+        });
+
+        (address, handle)
+    }
+
+    fn snd_nke() -> Frame {
+        Frame::new_short(Control::Initialize, Address::Primary(0x01))
+    }
+
+    #[test]
+    fn it_sends_a_frame_and_receives_the_reply() {
+        let (address, gateway) = gateway(1, |_, mut stream| {
+            let mut buffer = [0; 5];
+            stream.read_exact(&mut buffer).unwrap();
+            assert_eq!(buffer, SND_NKE);
+            stream.write_all(&ACK).unwrap();
+        });
+
+        let mut transport = TcpTransport::connect(address).unwrap();
+        transport.send(&snd_nke()).unwrap();
+        assert!(matches!(transport.receive().unwrap(), Frame::Single(_)));
+        gateway.join().unwrap();
+    }
+
+    #[test]
+    fn it_reassembles_a_frame_split_over_several_segments() {
+        let (address, gateway) = gateway(1, |_, mut stream| {
+            let mut buffer = [0; 5];
+            stream.read_exact(&mut buffer).unwrap();
+            for chunk in RSP_UD.chunks(5) {
+                stream.write_all(chunk).unwrap();
+                thread::sleep(Duration::from_millis(20));
+            }
+        });
+
+        let mut transport = TcpTransport::connect(address).unwrap();
+        transport.send(&snd_nke()).unwrap();
+        let frame = transport.receive().unwrap();
+        assert_eq!(frame.to_bytes(), RSP_UD);
+        gateway.join().unwrap();
+    }
+
+    #[test]
+    fn it_times_out_without_a_reply() {
+        let (address, gateway) = gateway(1, |_, mut stream| {
+            let mut buffer = [0; 5];
+            stream.read_exact(&mut buffer).unwrap();
+            thread::sleep(Duration::from_millis(100));
+        });
+
+        let mut transport = TcpTransport::connect(address)
+            .unwrap()
+            .with_timeout(Duration::from_millis(20));
+        transport.send(&snd_nke()).unwrap();
+        assert!(matches!(transport.receive(), Err(ReceiveError::Timeout)));
+        assert!(transport.is_connected());
+        gateway.join().unwrap();
+    }
+
+    #[test]
+    fn it_reconnects_after_the_gateway_closes_the_connection() {
+        let (address, gateway) = gateway(2, |index, mut stream| {
+            let mut buffer = [0; 5];
+            stream.read_exact(&mut buffer).unwrap();
+            if index == 1 {
+                stream.write_all(&ACK).unwrap();
+            }
+        });
+
+        let mut transport = TcpTransport::new(address).unwrap();
+        assert!(!transport.is_connected());
+
+        transport.send(&snd_nke()).unwrap();
+        assert!(matches!(transport.receive(), Err(ReceiveError::Transport(_))));
+        assert!(!transport.is_connected());
+
+        transport.send(&snd_nke()).unwrap();
+        assert!(matches!(transport.receive().unwrap(), Frame::Single(_)));
+        gateway.join().unwrap();
+    }
+
+    #[test]
+    fn it_drops_late_replies_before_sending() {
+        let (address, gateway) = gateway(1, |_, mut stream| {
+            let mut buffer = [0; 5];
+            stream.read_exact(&mut buffer).unwrap();
+            stream.write_all(&RSP_UD).unwrap();
+            stream.read_exact(&mut buffer).unwrap();
+            stream.write_all(&ACK).unwrap();
+        });
+
+        let mut transport = TcpTransport::connect(address).unwrap();
+        transport.send(&snd_nke()).unwrap();
+        thread::sleep(Duration::from_millis(50));
+
+        transport.send(&snd_nke()).unwrap();
+        assert!(matches!(transport.receive().unwrap(), Frame::Single(_)));
+        gateway.join().unwrap();
+    }
+
+    #[test]
+    fn it_waits_for_the_idle_time_between_exchanges() {
+        let (address, gateway) = gateway(1, |_, mut stream| {
+            let mut buffer = [0; 10];
+            stream.read_exact(&mut buffer).unwrap();
+        });
+
+        let mut transport = TcpTransport::connect(address)
+            .unwrap()
+            .with_idle_time(Duration::from_millis(50));
+        transport.send(&snd_nke()).unwrap();
+
+        let start = Instant::now();
+        transport.send(&snd_nke()).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        gateway.join().unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+/// Wireless M-Bus Control Function
+///
+/// The function carried by the C field of a wireless M-Bus frame, as defined
+/// in EN 13757-4 (§12.5). Bits 4 and 5 of the functions shared with the
+/// wired M-Bus hold the FCB/FCV or ACD/DFC bits, which don't change the
+/// function. Values that aren't explicitly modelled are kept as is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WMBusControl {
+    /// Initialize or reset the link (SND-NKE, 0x40)
+    Initialize,
+
+    /// Send user data to the meter (SND-UD, 0x53)
+    ///
+    /// The meter acknowledges the frame.
+    Send,
+
+    /// Send user data without reply (SND-NR, 0x44)
+    ///
+    /// This is the periodic telegram of a meter, which doesn't expect any
+    /// reply.
+    SendNoReply,
+
+    /// Send an installation request (SND-IR, 0x46)
+    ///
+    /// The meter expects a confirmation (CNF-IR) from the other station.
+    SendInstallationRequest,
+
+    /// Access the meter without reply (ACC-NR, 0x47)
+    ///
+    /// The meter signals that it's ready to receive frames, without any user
+    /// data to send.
+    AccessNoReply,
+
+    /// Access demand (ACC-DMD, 0x48)
+    ///
+    /// The meter demands access to the other station, e.g. after an alarm,
+    /// and expects an acknowledgment.
+    AccessDemand,
+
+    /// Request time-critical user data from the meter (REQ-UD1, 0x5A)
+    PriorityRequest,
+
+    /// Request user data from the meter (REQ-UD2, 0x5B)
+    Request,
+
+    /// Acknowledgment (ACK, 0x00)
+    Ack,
+
+    /// Confirm an installation request (CNF-IR, 0x06)
+    ConfirmInstallationRequest,
+
+    /// Respond with user data (RSP-UD, 0x08)
+    Response,
+
+    /// Any other C field value
+    Other(u8),
+}
+
+/// Implement conversion from u8 to WMBusControl
+impl From<u8> for WMBusControl {
+    fn from(value: u8) -> Self {
+        match value {
+            0x40 => WMBusControl::Initialize,
+            0x53 | 0x73 => WMBusControl::Send,
+            0x44 => WMBusControl::SendNoReply,
+            0x46 => WMBusControl::SendInstallationRequest,
+            0x47 => WMBusControl::AccessNoReply,
+            0x48 => WMBusControl::AccessDemand,
+            0x5A | 0x7A => WMBusControl::PriorityRequest,
+            0x5B | 0x7B => WMBusControl::Request,
+            0x00 | 0x10 | 0x20 | 0x30 => WMBusControl::Ack,
+            0x06 | 0x16 | 0x26 | 0x36 => WMBusControl::ConfirmInstallationRequest,
+            0x08 | 0x18 | 0x28 | 0x38 => WMBusControl::Response,
+            _ => WMBusControl::Other(value),
+        }
+    }
+}
+
+/// Implement conversion from WMBusControl to u8
+impl From<WMBusControl> for u8 {
+    fn from(control: WMBusControl) -> Self {
+        match control {
+            WMBusControl::Initialize => 0x40,
+            WMBusControl::Send => 0x53,
+            WMBusControl::SendNoReply => 0x44,
+            WMBusControl::SendInstallationRequest => 0x46,
+            WMBusControl::AccessNoReply => 0x47,
+            WMBusControl::AccessDemand => 0x48,
+            WMBusControl::PriorityRequest => 0x5A,
+            WMBusControl::Request => 0x5B,
+            WMBusControl::Ack => 0x00,
+            WMBusControl::ConfirmInstallationRequest => 0x06,
+            WMBusControl::Response => 0x08,
+            WMBusControl::Other(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_decodes_the_wireless_functions() {
+        assert_eq!(WMBusControl::from(0x44), WMBusControl::SendNoReply);
+        assert_eq!(WMBusControl::from(0x46), WMBusControl::SendInstallationRequest);
+        assert_eq!(WMBusControl::from(0x47), WMBusControl::AccessNoReply);
+        assert_eq!(WMBusControl::from(0x48), WMBusControl::AccessDemand);
+    }
+
+    #[test]
+    fn it_ignores_the_fcb_and_acd_bits() {
+        assert_eq!(WMBusControl::from(0x73), WMBusControl::Send);
+        assert_eq!(WMBusControl::from(0x7B), WMBusControl::Request);
+        assert_eq!(WMBusControl::from(0x38), WMBusControl::Response);
+        assert_eq!(WMBusControl::from(0x20), WMBusControl::Ack);
+    }
+
+    #[test]
+    fn it_keeps_unknown_values() {
+        assert_eq!(WMBusControl::from(0x43), WMBusControl::Other(0x43));
+        assert_eq!(u8::from(WMBusControl::Other(0x43)), 0x43);
+    }
+
+    #[test]
+    fn it_round_trips_the_default_value_of_every_function() {
+        for value in 0..=u8::MAX {
+            let control = WMBusControl::from(value);
+            assert_eq!(WMBusControl::from(u8::from(control)), control);
+        }
+    }
+}
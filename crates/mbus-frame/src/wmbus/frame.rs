@@ -0,0 +1,599 @@
+use crate::address::{SecondaryAddress, SECONDARY_ADDRESS_LENGTH};
+use crate::ci::CiField;
+use crate::frame::{output_buffer, payload_from_slice, EncodeError, Payload};
+use super::{TransportHeader, WMBusControl};
+use core::ops::Range;
+use mbus_meta::Manufacturer;
+use thiserror::Error;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Polynomial of the CRC of wireless M-Bus frames
+const CRC_POLYNOMIAL: u16 = 0x3D65;
+
+/// Length of the first block of a frame: the L, C, M and A fields
+const HEADER_LENGTH: usize = 10;
+
+/// Length of a CRC field
+const CRC_LENGTH: usize = 2;
+
+/// Length of the blocks following the first block in format A, without CRC
+const FORMAT_A_BLOCK_LENGTH: usize = 16;
+
+/// Length of the first and second blocks in format B, without the CRC
+const FORMAT_B_BLOCK_LENGTH: usize = 123; // 10 + 115 - 2
+
+/// Maximum length of the link data, from the L field to the end of the user
+/// data, without CRCs
+const MAX_LINK_LENGTH: usize = 256;
+
+const LENGTH_INDEX: usize = 0;
+const CONTROL_INDEX: usize = 1;
+const MANUFACTURER_INDEX: usize = 2;
+const ADDRESS_INDEX: usize = 4;
+const CI_INDEX: usize = 10;
+const DATA_START_INDEX: usize = 11;
+
+/// Wireless M-Bus Frame Format
+///
+/// EN 13757-4 (§12) defines two frame formats, which only differ in how the
+/// frame is split into blocks, each followed by a CRC.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FrameFormat {
+    /// Format A
+    ///
+    /// The first block holds the L, C, M and A fields, and every following
+    /// block up to 16 bytes of user data. The L field doesn't count the CRCs.
+    A,
+
+    /// Format B
+    ///
+    /// The first block holds the L, C, M and A fields without a CRC, the
+    /// second block up to 115 bytes including a CRC over both blocks, and an
+    /// optional third block the rest of the user data. The L field counts
+    /// the CRCs.
+    B,
+}
+
+impl FrameFormat {
+    /// Get the maximum length of the user data, following the CI field
+    pub fn max_data_length(&self) -> usize {
+        match self {
+            // L = 9 + CI + data
+            FrameFormat::A => 245,
+            // L = 9 + CI + data + 2 CRCs
+            FrameFormat::B => 241,
+        }
+    }
+
+    /// Get the ranges of the link data covered by each CRC, for link data of
+    /// the given length
+    fn blocks(&self, length: usize) -> impl Iterator<Item = Range<usize>> {
+        let (first, rest) = match self {
+            FrameFormat::A => (HEADER_LENGTH, FORMAT_A_BLOCK_LENGTH),
+            // The third block holds the rest of the user data, however long
+            FrameFormat::B => (FORMAT_B_BLOCK_LENGTH, MAX_LINK_LENGTH),
+        };
+
+        let mut start = 0;
+        core::iter::from_fn(move || {
+            if start >= length {
+                return None;
+            }
+
+            let end = length.min(if start == 0 { first } else { start + rest });
+            let block = start..end;
+            start = end;
+            Some(block)
+        })
+    }
+
+    /// Get the value of the L field for link data of the given length
+    fn length_field(&self, length: usize) -> usize {
+        match self {
+            FrameFormat::A => length - 1,
+            FrameFormat::B => length - 1 + CRC_LENGTH * self.blocks(length).count(),
+        }
+    }
+
+    /// Get the length of the link data, from the value of the L field
+    ///
+    /// Returns `None` if no link data has this L field, which can happen in
+    /// format B where the L field counts the CRCs.
+    fn link_length(&self, length_field: u8) -> Option<usize> {
+        let length = length_field as usize + 1;
+        match self {
+            FrameFormat::A => Some(length),
+            FrameFormat::B => [CRC_LENGTH, 2 * CRC_LENGTH]
+                .into_iter()
+                .filter_map(|crcs| length.checked_sub(crcs))
+                .filter(|&link| link > 0)
+                .find(|&link| self.length_field(link) == length_field as usize),
+        }
+    }
+}
+
+/// Compute the CRC of wireless M-Bus frames
+///
+/// The CRC is defined in EN 13757-4 (§12) with the polynomial 0x3D65, an
+/// initial value of 0 and a final inversion. It's sent most significant byte
+/// first.
+pub fn crc(bytes: &[u8]) -> u16 {
+    let crc = bytes.iter().fold(0u16, |crc, &byte| {
+        (0..8).fold(crc ^ ((byte as u16) << 8), |crc, _| {
+            if crc & 0x8000 != 0 {
+                (crc << 1) ^ CRC_POLYNOMIAL
+            } else {
+                crc << 1
+            }
+        })
+    });
+
+    !crc
+}
+
+/// Wireless M-Bus Frame
+///
+/// A wireless M-Bus frame carries the user data of a meter over the air, as
+/// defined in EN 13757-4 (§12). Its link layer is made of:
+///
+/// - the length (L) field, with the number of bytes following it,
+/// - the control (C) field, with the function of the frame,
+/// - the manufacturer (M) field, with the manufacturer of the meter,
+/// - the address (A) field, with the identification number, version and
+///   device type of the meter,
+///
+/// followed by the CI field and the user data. The whole frame is protected
+/// by CRCs, laid out according to the [`FrameFormat`]. The CRCs are checked
+/// and stripped when decoding, and added back when encoding.
+///
+/// The C field is classified as a [`WMBusControl`], and the CI field tells
+/// which [`TransportHeader`] precedes the application data.
+#[derive(Debug, Clone)]
+pub struct WMBusFrame {
+    /// Frame format
+    format: FrameFormat,
+
+    /// Control (C) field, with its FCB/ACD bits
+    control: u8,
+
+    /// Address of the meter, from the M and A fields
+    address: SecondaryAddress,
+
+    /// Control information
+    ci: CiField,
+
+    /// User data, following the control information byte
+    data: Payload,
+}
+
+impl WMBusFrame {
+    /// Create a new wireless M-Bus frame
+    ///
+    /// # Panics
+    ///
+    /// Panics if the user data is longer than the maximum length of the
+    /// format. Use [`WMBusFrame::try_new`] to handle oversize user data
+    /// gracefully.
+    pub fn new(format: FrameFormat, control: impl Into<u8>, address: SecondaryAddress, ci: CiField, data: &[u8]) -> Self {
+        Self::try_new(format, control, address, ci, data)
+            .expect("user data exceeds the maximum length of a wireless M-Bus frame")
+    }
+
+    /// Try creating a new wireless M-Bus frame
+    ///
+    /// Fails if the user data is longer than
+    /// [`FrameFormat::max_data_length`], as the L field can't exceed 255.
+    pub fn try_new(
+        format: FrameFormat,
+        control: impl Into<u8>,
+        address: SecondaryAddress,
+        ci: CiField,
+        data: &[u8],
+    ) -> Result<Self, WMBusFrameEncodeError> {
+        if data.len() > format.max_data_length() {
+            return Err(WMBusFrameEncodeError::DataTooLong(data.len(), format.max_data_length()));
+        }
+
+        Ok(Self {
+            format,
+            control: control.into(),
+            address,
+            ci,
+            data: payload_from_slice(data),
+        })
+    }
+
+    /// Get the format of the frame
+    pub fn format(&self) -> FrameFormat {
+        self.format
+    }
+
+    /// Get the function of the control (C) field of the frame
+    pub fn control(&self) -> WMBusControl {
+        self.control.into()
+    }
+
+    /// Get the raw control (C) field of the frame, with its FCB/ACD bits
+    pub fn control_byte(&self) -> u8 {
+        self.control
+    }
+
+    /// Get the address of the meter, from the M and A fields
+    pub fn address(&self) -> SecondaryAddress {
+        self.address
+    }
+
+    /// Get the manufacturer of the meter, from the M field
+    pub fn manufacturer(&self) -> Manufacturer {
+        self.address.manufacturer()
+    }
+
+    /// Get the control information field of the frame
+    pub fn ci(&self) -> CiField {
+        self.ci
+    }
+
+    /// Get the user data of the frame, following the control information field
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the transport layer header announced by the CI field, if any
+    pub fn transport_header(&self) -> Option<TransportHeader> {
+        TransportHeader::from_ci(self.ci)
+    }
+
+    /// Get the transport layer header bytes, following the CI field
+    ///
+    /// Returns `None` if the CI field doesn't announce a transport layer
+    /// header, or if the user data is shorter than the header.
+    pub fn header(&self) -> Option<&[u8]> {
+        self.data.get(..self.transport_header()?.length())
+    }
+
+    /// Get the application data, following the transport layer header
+    ///
+    /// Returns `None` if the CI field doesn't announce a transport layer
+    /// header, or if the user data is shorter than the header.
+    pub fn application_data(&self) -> Option<&[u8]> {
+        self.data.get(self.transport_header()?.length()..)
+    }
+
+    /// Get the number of bytes of the link data, without CRCs
+    fn link_length(&self) -> usize {
+        DATA_START_INDEX + self.data.len()
+    }
+
+    /// Get the number of bytes of the encoded frame, including CRCs
+    pub fn encoded_len(&self) -> usize {
+        let length = self.link_length();
+        length + CRC_LENGTH * self.format.blocks(length).count()
+    }
+
+    /// Encode the frame into a byte buffer, returning the number of bytes written
+    pub fn encode_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let buf = output_buffer(buf, self.encoded_len())?;
+
+        // Lay out the link data first, then split it into blocks
+        let length = self.link_length();
+        let mut link = [0u8; MAX_LINK_LENGTH];
+        let address = self.address.to_bytes();
+        link[LENGTH_INDEX] = self.format.length_field(length) as u8;
+        link[CONTROL_INDEX] = self.control;
+        // The M field comes first, followed by the rest of the secondary address
+        link[MANUFACTURER_INDEX..ADDRESS_INDEX].copy_from_slice(&address[4..6]);
+        link[ADDRESS_INDEX..ADDRESS_INDEX + 4].copy_from_slice(&address[..4]);
+        link[ADDRESS_INDEX + 4..CI_INDEX].copy_from_slice(&address[6..]);
+        link[CI_INDEX] = self.ci.into();
+        link[DATA_START_INDEX..length].copy_from_slice(&self.data);
+
+        let mut position = 0;
+        for block in self.format.blocks(length) {
+            let bytes = &link[block];
+            buf[position..position + bytes.len()].copy_from_slice(bytes);
+            position += bytes.len();
+            buf[position..position + CRC_LENGTH].copy_from_slice(&crc(bytes).to_be_bytes());
+            position += CRC_LENGTH;
+        }
+
+        Ok(position)
+    }
+
+    /// Convert the frame to a byte vector
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = alloc::vec![0; self.encoded_len()];
+        self.encode_into(&mut bytes)
+            .expect("buffer is sized to the encoded length");
+        bytes
+    }
+
+    /// Try decoding a byte slice into a wireless M-Bus frame of the given
+    /// format
+    ///
+    /// The format isn't encoded in the frame itself, but signalled by the
+    /// radio, e.g. through the synchronization word of the mode.
+    pub fn try_from_bytes(bytes: &[u8], format: FrameFormat) -> Result<Self, WMBusFrameDecodeError> {
+        let length_field = *bytes.first().ok_or(WMBusFrameDecodeError::InvalidSize(0))?;
+
+        // Ensure that the L field covers at least the C, M, A and CI fields
+        let length = format
+            .link_length(length_field)
+            .filter(|&length| length > CI_INDEX)
+            .ok_or(WMBusFrameDecodeError::InvalidLengthField(length_field))?;
+
+        // Ensure that the size of the frame matches the L field
+        let expected_size = length + CRC_LENGTH * format.blocks(length).count();
+        if bytes.len() != expected_size {
+            return Err(WMBusFrameDecodeError::InvalidLength(expected_size, bytes.len()));
+        }
+
+        // Ensure that the CRC of each block is correct, and strip it
+        let mut link = [0u8; MAX_LINK_LENGTH];
+        let mut position = 0;
+        for (index, block) in format.blocks(length).enumerate() {
+            let size = block.len();
+            let data = &bytes[position..position + size];
+            let received = u16::from_be_bytes([bytes[position + size], bytes[position + size + 1]]);
+            let computed = crc(data);
+            if computed != received {
+                return Err(WMBusFrameDecodeError::InvalidCrc(index, computed, received));
+            }
+
+            link[block].copy_from_slice(data);
+            position += size + CRC_LENGTH;
+        }
+
+        let mut address = [0u8; SECONDARY_ADDRESS_LENGTH];
+        address[..4].copy_from_slice(&link[ADDRESS_INDEX..ADDRESS_INDEX + 4]);
+        address[4..6].copy_from_slice(&link[MANUFACTURER_INDEX..ADDRESS_INDEX]);
+        address[6..].copy_from_slice(&link[ADDRESS_INDEX + 4..CI_INDEX]);
+
+        Ok(Self {
+            format,
+            control: link[CONTROL_INDEX],
+            address: SecondaryAddress::from_bytes(&address),
+            ci: link[CI_INDEX].into(),
+            data: payload_from_slice(&link[DATA_START_INDEX..length]),
+        })
+    }
+}
+
+/// Errors that can occur when creating a wireless M-Bus frame
+#[derive(Error, Debug)]
+pub enum WMBusFrameEncodeError {
+    #[error("user data too long for wireless M-Bus frame, expected <={1} bytes, got {0}")]
+    DataTooLong(usize, usize),
+}
+
+/// Errors that can occur when decoding a wireless M-Bus frame
+#[derive(Error, Debug)]
+pub enum WMBusFrameDecodeError {
+    #[error("invalid frame size for wireless M-Bus frame, got {0}")]
+    InvalidSize(usize),
+    #[error("invalid L field for wireless M-Bus frame: {0}")]
+    InvalidLengthField(u8),
+    #[error("invalid length for wireless M-Bus frame, expected {0} bytes, got {1}")]
+    InvalidLength(usize, usize),
+    #[error("invalid CRC for block {0} of wireless M-Bus frame, expected {1:#06x}, got {2:#06x}")]
+    InvalidCrc(usize, u16, u16),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use proptest::prelude::*;
+    #[cfg(feature = "alloc")]
+    use alloc::{vec, vec::Vec};
+
+    /// Create an SND-NR from a water meter, with a short header (CI 0x7A)
+    fn try_frame(format: FrameFormat, data: &[u8]) -> Result<WMBusFrame, WMBusFrameEncodeError> {
+        let address = SecondaryAddress::from_raw(0x12345678, 0x2C2D, 0x1B, 0x07);
+        WMBusFrame::try_new(format, WMBusControl::SendNoReply, address, CiField::VariableDataResponseShortHeader, data)
+    }
+
+    fn frame(format: FrameFormat, data: &[u8]) -> WMBusFrame {
+        try_frame(format, data).unwrap()
+    }
+
+    fn frame_with_ci(ci: CiField, data: &[u8]) -> WMBusFrame {
+        let address = SecondaryAddress::from_raw(0x12345678, 0x2C2D, 0x1B, 0x07);
+        WMBusFrame::new(FrameFormat::A, WMBusControl::SendNoReply, address, ci, data)
+    }
+
+    #[test]
+    fn it_computes_the_crc() {
+        assert_eq!(crc(b"123456789"), 0xC2B7);
+        assert_eq!(crc(&[]), 0xFFFF);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_encodes_a_format_a_frame() {
+        let bytes = frame(FrameFormat::A, &[0x01, 0x02, 0x03]).to_bytes();
+
+        let header = [0x0D, 0x44, 0x2D, 0x2C, 0x78, 0x56, 0x34, 0x12, 0x1B, 0x07];
+        assert_eq!(bytes.len(), 18);
+        assert_eq!(bytes[..10], header);
+        assert_eq!(bytes[10..12], crc(&header).to_be_bytes());
+        assert_eq!(bytes[12..16], [0x7A, 0x01, 0x02, 0x03]);
+        assert_eq!(bytes[16..], crc(&bytes[12..16]).to_be_bytes());
+    }
+
+    #[test]
+    fn it_round_trips_a_frame_encoded_into_a_buffer() {
+        for format in [FrameFormat::A, FrameFormat::B] {
+            let frame = frame(format, &[0x01, 0x02, 0x03]);
+            let mut buf = [0u8; 32];
+            let length = frame.encode_into(&mut buf).unwrap();
+            assert_eq!(length, frame.encoded_len());
+            let decoded = WMBusFrame::try_from_bytes(&buf[..length], format).unwrap();
+            assert_eq!(decoded.data(), &[0x01, 0x02, 0x03]);
+        }
+    }
+
+    #[test]
+    fn it_fails_to_encode_a_frame_into_a_buffer_too_small() {
+        let frame = frame(FrameFormat::A, &[0x01, 0x02, 0x03]);
+        let mut buf = [0u8; 17];
+        assert!(matches!(frame.encode_into(&mut buf), Err(EncodeError::BufferTooSmall(18, 17))));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_splits_format_a_frames_into_blocks_of_16_bytes() {
+        let data: Vec<u8> = (0..20).collect();
+        let bytes = frame(FrameFormat::A, &data).to_bytes();
+
+        // 12 bytes for the first block, 16 + 2 for the second and 5 + 2 for the last
+        assert_eq!(bytes.len(), 12 + 18 + 7);
+        assert_eq!(bytes[0], 30);
+        assert_eq!(bytes[28..30], crc(&bytes[12..28]).to_be_bytes());
+        assert_eq!(bytes[35..], crc(&bytes[30..35]).to_be_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_encodes_a_format_b_frame() {
+        let bytes = frame(FrameFormat::B, &[0x01, 0x02, 0x03]).to_bytes();
+
+        // The L field counts the CRC, which covers both blocks
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(bytes[0], 0x0F);
+        assert_eq!(bytes[14..], crc(&bytes[..14]).to_be_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_adds_a_third_block_to_long_format_b_frames() {
+        let data = [0xAA; 120];
+        let bytes = frame(FrameFormat::B, &data).to_bytes();
+
+        assert_eq!(bytes.len(), 131 + 4);
+        assert_eq!(bytes[0], 134);
+        assert_eq!(bytes[123..125], crc(&bytes[..123]).to_be_bytes());
+        assert_eq!(bytes[133..], crc(&bytes[125..133]).to_be_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_decodes_the_link_layer_fields() {
+        for format in [FrameFormat::A, FrameFormat::B] {
+            let bytes = frame(format, &[0x01, 0x02, 0x03]).to_bytes();
+            let frame = WMBusFrame::try_from_bytes(&bytes, format).unwrap();
+
+            assert_eq!(frame.format(), format);
+            assert_eq!(frame.control(), WMBusControl::SendNoReply);
+            assert_eq!(frame.manufacturer(), Manufacturer::from(0x2C2D));
+            assert_eq!(frame.address().id(), Some(12345678));
+            assert_eq!(frame.address().version(), 0x1B);
+            assert_eq!(u8::from(frame.address().medium()), 0x07);
+            assert_eq!(frame.ci(), CiField::VariableDataResponseShortHeader);
+            assert_eq!(frame.data(), [0x01, 0x02, 0x03]);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_keeps_the_fcb_and_acd_bits_of_the_control_field() {
+        let address = SecondaryAddress::from_raw(0x12345678, 0x2C2D, 0x1B, 0x07);
+        let frame = WMBusFrame::new(FrameFormat::A, 0x38, address, CiField::VariableDataResponse, &[]);
+
+        assert_eq!(frame.control(), WMBusControl::Response);
+        assert_eq!(frame.control_byte(), 0x38);
+        assert_eq!(frame.to_bytes()[1], 0x38);
+    }
+
+    #[test]
+    fn it_splits_the_transport_header_from_the_application_data() {
+        let frame = frame(FrameFormat::A, &[0x2A, 0x00, 0x00, 0x00, 0x0C, 0x13, 0x01]);
+        assert_eq!(frame.transport_header(), Some(TransportHeader::Short));
+        assert_eq!(frame.header(), Some(&[0x2A, 0x00, 0x00, 0x00][..]));
+        assert_eq!(frame.application_data(), Some(&[0x0C, 0x13, 0x01][..]));
+
+        // The user data is too short for the header
+        assert_eq!(frame_with_ci(CiField::VariableDataResponse, &[0x01]).header(), None);
+
+        // Extended link layers aren't classified
+        assert_eq!(frame_with_ci(CiField::ExtendedLinkLayerI, &[0x01]).application_data(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_rejects_an_invalid_crc() {
+        let mut bytes = frame(FrameFormat::A, &[0x01; 20]).to_bytes();
+        bytes[20] ^= 0x01;
+
+        let err = WMBusFrame::try_from_bytes(&bytes, FrameFormat::A).unwrap_err();
+        assert!(matches!(err, WMBusFrameDecodeError::InvalidCrc(1, _, _)));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_rejects_a_length_mismatch() {
+        let bytes = frame(FrameFormat::A, &[0x01; 20]).to_bytes();
+
+        let err = WMBusFrame::try_from_bytes(&bytes[..bytes.len() - 1], FrameFormat::A).unwrap_err();
+        assert!(matches!(err, WMBusFrameDecodeError::InvalidLength(37, 36)));
+
+        let err = WMBusFrame::try_from_bytes(&bytes, FrameFormat::B).unwrap_err();
+        assert!(matches!(err, WMBusFrameDecodeError::InvalidLength(..)));
+    }
+
+    #[test]
+    fn it_rejects_an_invalid_length_field() {
+        assert!(matches!(
+            WMBusFrame::try_from_bytes(&[], FrameFormat::A),
+            Err(WMBusFrameDecodeError::InvalidSize(0))
+        ));
+        assert!(matches!(
+            WMBusFrame::try_from_bytes(&[0x09], FrameFormat::A),
+            Err(WMBusFrameDecodeError::InvalidLengthField(0x09))
+        ));
+        // In format B, no link data can be split into blocks adding up to L = 125
+        assert!(matches!(
+            WMBusFrame::try_from_bytes(&[125], FrameFormat::B),
+            Err(WMBusFrameDecodeError::InvalidLengthField(125))
+        ));
+    }
+
+    #[cfg(feature = "alloc")]
+    proptest! {
+        #[test]
+        fn it_round_trips_any_valid_frame(
+            format in prop::sample::select(vec![FrameFormat::A, FrameFormat::B]),
+            control: u8,
+            id: u32,
+            manufacturer: u16,
+            version: u8,
+            medium: u8,
+            ci: u8,
+            data in prop::collection::vec(any::<u8>(), 0..=245usize),
+        ) {
+            let data = &data[..data.len().min(format.max_data_length())];
+            let address = SecondaryAddress::from_raw(id, manufacturer, version, medium);
+            let frame = WMBusFrame::new(format, control, address, ci.into(), data);
+            let bytes = frame.to_bytes();
+            prop_assert_eq!(bytes.len(), frame.encoded_len());
+
+            let decoded = WMBusFrame::try_from_bytes(&bytes, format).unwrap();
+            prop_assert_eq!(decoded.control_byte(), control);
+            prop_assert_eq!(decoded.address(), address);
+            prop_assert_eq!(decoded.data(), data);
+            prop_assert_eq!(decoded.to_bytes(), bytes);
+        }
+
+        #[test]
+        fn it_rejects_any_oversize_data(
+            format in prop::sample::select(vec![FrameFormat::A, FrameFormat::B]),
+            excess in 1..1024usize,
+        ) {
+            let length = format.max_data_length() + excess;
+            let result = try_frame(format, &vec![0u8; length]);
+            prop_assert!(matches!(result, Err(WMBusFrameEncodeError::DataTooLong(l, _)) if l == length));
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::ci::CiField;
+
+/// Length of a short transport layer header
+const SHORT_HEADER_LENGTH: usize = 4;
+
+/// Length of a long transport layer header
+const LONG_HEADER_LENGTH: usize = 12;
+
+/// Transport Layer Header
+///
+/// The CI field of a wireless telegram tells which transport layer header
+/// precedes the application data, as defined in EN 13757-7 (§7):
+///
+/// - a short header carries the access number, the status and the
+///   configuration field,
+/// - a long header carries the identification number, manufacturer, version
+///   and device type of the meter in addition, e.g. when the telegram is
+///   relayed by another device,
+/// - the application data may also directly follow the CI field.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TransportHeader {
+    /// No header (CI 0x78)
+    None,
+
+    /// Short header (CI 0x7A)
+    Short,
+
+    /// Long header (CI 0x72)
+    Long,
+}
+
+impl TransportHeader {
+    /// Get the transport layer header announced by a CI field
+    ///
+    /// Returns `None` for other CI fields, such as extended link layers.
+    pub fn from_ci(ci: CiField) -> Option<Self> {
+        match ci {
+            CiField::VariableDataResponseNoHeader => Some(TransportHeader::None),
+            CiField::VariableDataResponseShortHeader => Some(TransportHeader::Short),
+            CiField::VariableDataResponse => Some(TransportHeader::Long),
+            _ => None,
+        }
+    }
+
+    /// Get the CI field announcing this transport layer header
+    pub fn ci(&self) -> CiField {
+        match self {
+            TransportHeader::None => CiField::VariableDataResponseNoHeader,
+            TransportHeader::Short => CiField::VariableDataResponseShortHeader,
+            TransportHeader::Long => CiField::VariableDataResponse,
+        }
+    }
+
+    /// Get the number of bytes of the header
+    pub fn length(&self) -> usize {
+        match self {
+            TransportHeader::None => 0,
+            TransportHeader::Short => SHORT_HEADER_LENGTH,
+            TransportHeader::Long => LONG_HEADER_LENGTH,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_classifies_the_ci_field() {
+        assert_eq!(TransportHeader::from_ci(0x78.into()), Some(TransportHeader::None));
+        assert_eq!(TransportHeader::from_ci(0x7A.into()), Some(TransportHeader::Short));
+        assert_eq!(TransportHeader::from_ci(0x72.into()), Some(TransportHeader::Long));
+        assert_eq!(TransportHeader::from_ci(0x8C.into()), None);
+    }
+
+    #[test]
+    fn it_round_trips_the_ci_field() {
+        for header in [TransportHeader::None, TransportHeader::Short, TransportHeader::Long] {
+            assert_eq!(TransportHeader::from_ci(header.ci()), Some(header));
+        }
+    }
+}
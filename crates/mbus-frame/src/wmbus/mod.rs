@@ -0,0 +1,7 @@
+mod control;
+mod frame;
+mod header;
+
+pub use control::WMBusControl;
+pub use frame::{crc, FrameFormat, WMBusFrame, WMBusFrameDecodeError, WMBusFrameEncodeError};
+pub use header::TransportHeader;
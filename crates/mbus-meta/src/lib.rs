@@ -1,5 +1,9 @@
+#![no_std]
+
 mod device;
 mod manufacturer;
+mod medium;
 
 pub use device::Device;
 pub use manufacturer::Manufacturer;
+pub use medium::Medium;
@@ -117,6 +117,7 @@ pub enum Manufacturer {
     Unknown = 0x0000,
 }
 
+#[allow(dead_code)]
 pub struct ManufacturerInfo {
     pub id: u16,
     pub name: &'static str,
@@ -364,3 +365,9 @@ impl From<u16> for Manufacturer {
         }
     }
 }
+
+impl From<Manufacturer> for u16 {
+    fn from(manufacturer: Manufacturer) -> Self {
+        manufacturer as u16
+    }
+}
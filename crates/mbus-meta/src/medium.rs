@@ -0,0 +1,173 @@
+/// M-Bus Medium (Device Type) Identifiers
+///
+/// The medium identifies the type of device, as defined in EN 13757-3
+/// (Table 3). Values that aren't assigned are kept as is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Medium {
+    Other,
+    Oil,
+    Electricity,
+    Gas,
+    HeatOutlet,
+    Steam,
+    WarmWater,
+    Water,
+    HeatCostAllocator,
+    CompressedAir,
+    CoolingLoadOutlet,
+    CoolingLoadInlet,
+    HeatInlet,
+    HeatCoolingLoad,
+    BusSystemComponent,
+    Unknown,
+    CalorificValue,
+    HotWater,
+    ColdWater,
+    DualRegisterWater,
+    Pressure,
+    AdConverter,
+    SmokeDetector,
+    RoomSensor,
+    GasDetector,
+    Breaker,
+    Valve,
+    CustomerUnit,
+    WasteWater,
+    Garbage,
+    CommunicationController,
+    UnidirectionalRepeater,
+    BidirectionalRepeater,
+    RadioConverterSystemSide,
+    RadioConverterMeterSide,
+    Reserved(u8),
+}
+
+impl Medium {
+    /// Get the name of the medium
+    pub fn name(&self) -> &'static str {
+        match self {
+            Medium::Other => "Other",
+            Medium::Oil => "Oil",
+            Medium::Electricity => "Electricity",
+            Medium::Gas => "Gas",
+            Medium::HeatOutlet => "Heat (outlet)",
+            Medium::Steam => "Steam",
+            Medium::WarmWater => "Warm water (30 °C - 90 °C)",
+            Medium::Water => "Water",
+            Medium::HeatCostAllocator => "Heat cost allocator",
+            Medium::CompressedAir => "Compressed air",
+            Medium::CoolingLoadOutlet => "Cooling load meter (outlet)",
+            Medium::CoolingLoadInlet => "Cooling load meter (inlet)",
+            Medium::HeatInlet => "Heat (inlet)",
+            Medium::HeatCoolingLoad => "Heat / cooling load meter",
+            Medium::BusSystemComponent => "Bus / system component",
+            Medium::Unknown => "Unknown medium",
+            Medium::CalorificValue => "Calorific value",
+            Medium::HotWater => "Hot water (>= 90 °C)",
+            Medium::ColdWater => "Cold water",
+            Medium::DualRegisterWater => "Dual register (hot/cold) water",
+            Medium::Pressure => "Pressure",
+            Medium::AdConverter => "A/D converter",
+            Medium::SmokeDetector => "Smoke detector",
+            Medium::RoomSensor => "Room sensor",
+            Medium::GasDetector => "Gas detector",
+            Medium::Breaker => "Breaker (electricity)",
+            Medium::Valve => "Valve (gas or water)",
+            Medium::CustomerUnit => "Customer unit (display device)",
+            Medium::WasteWater => "Waste water",
+            Medium::Garbage => "Garbage",
+            Medium::CommunicationController => "Communication controller",
+            Medium::UnidirectionalRepeater => "Unidirectional repeater",
+            Medium::BidirectionalRepeater => "Bidirectional repeater",
+            Medium::RadioConverterSystemSide => "Radio converter (system side)",
+            Medium::RadioConverterMeterSide => "Radio converter (meter side)",
+            Medium::Reserved(_) => "Reserved",
+        }
+    }
+}
+
+impl From<u8> for Medium {
+    fn from(value: u8) -> Self {
+        match value {
+            0x00 => Medium::Other,
+            0x01 => Medium::Oil,
+            0x02 => Medium::Electricity,
+            0x03 => Medium::Gas,
+            0x04 => Medium::HeatOutlet,
+            0x05 => Medium::Steam,
+            0x06 => Medium::WarmWater,
+            0x07 => Medium::Water,
+            0x08 => Medium::HeatCostAllocator,
+            0x09 => Medium::CompressedAir,
+            0x0A => Medium::CoolingLoadOutlet,
+            0x0B => Medium::CoolingLoadInlet,
+            0x0C => Medium::HeatInlet,
+            0x0D => Medium::HeatCoolingLoad,
+            0x0E => Medium::BusSystemComponent,
+            0x0F => Medium::Unknown,
+            0x14 => Medium::CalorificValue,
+            0x15 => Medium::HotWater,
+            0x16 => Medium::ColdWater,
+            0x17 => Medium::DualRegisterWater,
+            0x18 => Medium::Pressure,
+            0x19 => Medium::AdConverter,
+            0x1A => Medium::SmokeDetector,
+            0x1B => Medium::RoomSensor,
+            0x1C => Medium::GasDetector,
+            0x20 => Medium::Breaker,
+            0x21 => Medium::Valve,
+            0x25 => Medium::CustomerUnit,
+            0x28 => Medium::WasteWater,
+            0x29 => Medium::Garbage,
+            0x31 => Medium::CommunicationController,
+            0x32 => Medium::UnidirectionalRepeater,
+            0x33 => Medium::BidirectionalRepeater,
+            0x36 => Medium::RadioConverterSystemSide,
+            0x37 => Medium::RadioConverterMeterSide,
+            _ => Medium::Reserved(value),
+        }
+    }
+}
+
+impl From<Medium> for u8 {
+    fn from(medium: Medium) -> Self {
+        match medium {
+            Medium::Other => 0x00,
+            Medium::Oil => 0x01,
+            Medium::Electricity => 0x02,
+            Medium::Gas => 0x03,
+            Medium::HeatOutlet => 0x04,
+            Medium::Steam => 0x05,
+            Medium::WarmWater => 0x06,
+            Medium::Water => 0x07,
+            Medium::HeatCostAllocator => 0x08,
+            Medium::CompressedAir => 0x09,
+            Medium::CoolingLoadOutlet => 0x0A,
+            Medium::CoolingLoadInlet => 0x0B,
+            Medium::HeatInlet => 0x0C,
+            Medium::HeatCoolingLoad => 0x0D,
+            Medium::BusSystemComponent => 0x0E,
+            Medium::Unknown => 0x0F,
+            Medium::CalorificValue => 0x14,
+            Medium::HotWater => 0x15,
+            Medium::ColdWater => 0x16,
+            Medium::DualRegisterWater => 0x17,
+            Medium::Pressure => 0x18,
+            Medium::AdConverter => 0x19,
+            Medium::SmokeDetector => 0x1A,
+            Medium::RoomSensor => 0x1B,
+            Medium::GasDetector => 0x1C,
+            Medium::Breaker => 0x20,
+            Medium::Valve => 0x21,
+            Medium::CustomerUnit => 0x25,
+            Medium::WasteWater => 0x28,
+            Medium::Garbage => 0x29,
+            Medium::CommunicationController => 0x31,
+            Medium::UnidirectionalRepeater => 0x32,
+            Medium::BidirectionalRepeater => 0x33,
+            Medium::RadioConverterSystemSide => 0x36,
+            Medium::RadioConverterMeterSide => 0x37,
+            Medium::Reserved(value) => value,
+        }
+    }
+}